@@ -0,0 +1,53 @@
+//! Benchmarks for the `Amount` type.
+
+#[macro_use]
+extern crate criterion;
+extern crate fractal_utils;
+
+use criterion::{Criterion, black_box};
+
+use fractal_utils::Amount;
+
+/// Representative amount strings: whole amounts, usual decimals, the maximum amount and inputs
+/// with many decimals that need rounding.
+const PARSE_INPUTS: &[&str] = &["175",
+                                "175.646",
+                                ".6465",
+                                "18446744073709551.615",
+                                "175.6465",
+                                "175.64600000000000001"];
+
+/// Parses the amount strings with `FromStr`.
+fn bench_parse(c: &mut Criterion) {
+    c.bench_function_over_inputs("amount_parse",
+                                 |b, input| b.iter(|| black_box(input).parse::<Amount>()),
+                                 PARSE_INPUTS.iter().cloned());
+}
+
+/// Amount representations: zero, whole amounts, usual decimals and the maximum amount.
+const DISPLAY_INPUTS: &[u64] = &[0, 175_000, 175_646, 500, 18_446_744_073_709_551_615];
+
+/// Formats the amounts with `to_string()`, with a precision and with zero padding.
+fn bench_display(c: &mut Criterion) {
+    c.bench_function_over_inputs("amount_to_string",
+                                 |b, &repr| {
+                                     let amount = Amount::from_repr(repr);
+                                     b.iter(|| black_box(&amount).to_string())
+                                 },
+                                 DISPLAY_INPUTS.iter().cloned());
+    c.bench_function_over_inputs("amount_format_rounded",
+                                 |b, &repr| {
+                                     let amount = Amount::from_repr(repr);
+                                     b.iter(|| format!("{:.2}", black_box(&amount)))
+                                 },
+                                 DISPLAY_INPUTS.iter().cloned());
+    c.bench_function_over_inputs("amount_format_padded",
+                                 |b, &repr| {
+                                     let amount = Amount::from_repr(repr);
+                                     b.iter(|| format!("{:010.1}", black_box(&amount)))
+                                 },
+                                 DISPLAY_INPUTS.iter().cloned());
+}
+
+criterion_group!(benches, bench_parse, bench_display);
+criterion_main!(benches);
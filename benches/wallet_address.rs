@@ -0,0 +1,62 @@
+//! Benchmarks for the `WalletAddress` type.
+
+#[macro_use]
+extern crate criterion;
+extern crate rand;
+extern crate fractal_utils;
+
+use criterion::{Criterion, black_box};
+use rand::{Rng, thread_rng};
+
+use fractal_utils::{WALLET_ADDRESS_LEN, WalletAddress};
+
+/// Number of random addresses in each benchmark batch.
+const BATCH_SIZE: usize = 100;
+
+/// Generates a batch of random wallet addresses.
+fn random_addresses() -> Vec<WalletAddress> {
+    let mut rng = thread_rng();
+    (0..BATCH_SIZE)
+        .map(|_| {
+            let mut address = [0u8; WALLET_ADDRESS_LEN];
+            rng.fill_bytes(&mut address[1..]);
+            WalletAddress::from_data(address)
+        })
+        .collect()
+}
+
+fn bench_display(c: &mut Criterion) {
+    let addresses = random_addresses();
+    c.bench_function("wallet_address_to_string", move |b| {
+        b.iter(|| {
+            for address in &addresses {
+                let _ = black_box(black_box(address).to_string());
+            }
+        })
+    });
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let strings: Vec<String> = random_addresses().iter().map(|a| a.to_string()).collect();
+    c.bench_function("wallet_address_parse", move |b| {
+        b.iter(|| {
+            for string in &strings {
+                let _ = black_box(black_box(string).parse::<WalletAddress>());
+            }
+        })
+    });
+}
+
+fn bench_round_trip(c: &mut Criterion) {
+    let addresses = random_addresses();
+    c.bench_function("wallet_address_round_trip", move |b| {
+        b.iter(|| {
+            for address in &addresses {
+                let _ = black_box(black_box(address).to_string().parse::<WalletAddress>());
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_display, bench_parse, bench_round_trip);
+criterion_main!(benches);
@@ -1,8 +1,8 @@
 //! Fractal Global Credits amount
 //!
-//! This module holds the `Amount` type and the `AmountParseError`. It will eventually hold `MAX`
-//! and `MIN` values for `Amount`s when constant expressions are implemented in the compiler in the
-//! stable chanel.
+//! This module holds the `Amount` type, the `AmountParseError` and the `RoundingMode`. It will
+//! eventually hold `MAX` and `MIN` values for `Amount`s when constant expressions are implemented
+//! in the compiler in the stable chanel.
 //!
 //! The maximum and minimum amount values can in any case be known by using `max_value()` and
 //! `min_value()` functions in the `Amount` type:
@@ -20,17 +20,25 @@
 
 #![allow(trivial_numeric_casts)]
 
-use std::convert::From;
-use std::{fmt, str, u64};
+use std::convert::{From, TryFrom};
+use std::{cmp, fmt, iter, str, u64};
 use std::str::FromStr;
+use std::fmt::Write;
 use std::result::Result;
 use std::error::Error;
 use std::ops::{Add, AddAssign, Sub, SubAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign};
 use std::num::ParseIntError;
+use std::io;
 
 use rustc_serialize::{Encodable, Decodable, Encoder, Decoder};
 #[cfg(feature = "json-types")]
 use rustc_serialize::json;
+#[cfg(feature = "schemars")]
+use schemars::JsonSchema;
+#[cfg(feature = "schemars")]
+use schemars::gen::SchemaGenerator;
+#[cfg(feature = "schemars")]
+use schemars::schema::{Schema, SchemaObject, InstanceType, Metadata, NumberValidation};
 
 use super::CURRENCY_SYMBOL;
 
@@ -81,6 +89,23 @@ use super::CURRENCY_SYMBOL;
 /// assert_eq!(amount, Amount::from_repr(333)); // 0.333
 /// ```
 ///
+/// Note that, while divisions and multiplications are scalar, the remainder is computed with a
+/// divisor of whole credits: `amount % n` is what's left after taking as many `n` credit amounts
+/// as possible, so `amount % 1u8` gives the decimals of the amount. This means that `amount / n`
+/// and `amount % n` are not related as they are for integers. Instead, taking `n` credits `q`
+/// times, the relationship is the following:
+///
+/// ```
+/// # use fractal_utils::Amount;
+/// #
+/// let amount = Amount::from_repr(12_345); // 12.345
+/// assert_eq!(amount % 10u8, Amount::from_repr(2_345)); // 2.345
+///
+/// let n = 10;
+/// let q = amount.units() / n;
+/// assert_eq!(Amount::from_units(n).unwrap() * q + amount % n, amount);
+/// ```
+///
 /// Amounts can easily be displayed using the `Display` trait as any other number:
 ///
 /// ```
@@ -96,7 +121,10 @@ use super::CURRENCY_SYMBOL;
 /// let amount = Amount::from_repr(56); // 0.056
 /// assert_eq!(format!("{:.2}", amount), "0.06");
 /// ```
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+///
+/// Since an amount is only defined by its internal representation, equal amounts always have the
+/// same hash, no matter how they were created, so they can be used as keys in hash maps.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Amount {
     value: u64,
 }
@@ -112,6 +140,84 @@ impl Amount {
         self.value
     }
 
+    /// Returns the whole credits of the amount, without its decimals.
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// assert_eq!(Amount::from_repr(175_646).units(), 175);
+    /// ```
+    pub fn units(&self) -> u64 {
+        self.value / 1_000
+    }
+
+    /// Returns the thousandths of the amount, the decimals after its whole credits.
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// assert_eq!(Amount::from_repr(175_646).millis(), 646);
+    /// ```
+    pub fn millis(&self) -> u16 {
+        (self.value % 1_000) as u16
+    }
+
+    /// Creates an amount of whole credits, returning `None` if it's bigger than the maximum amount.
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// assert_eq!(Amount::from_units(175), Some(Amount::from_repr(175_000)));
+    /// assert_eq!(Amount::from_units(u64::max_value()), None);
+    /// ```
+    pub fn from_units(units: u64) -> Option<Amount> {
+        units.checked_mul(1_000).map(Amount::from_repr)
+    }
+
+    /// Creates an amount from its units and its thousandths.
+    ///
+    /// It returns `None` if the thousandths are not between `0` and `999`, or if the amount is
+    /// bigger than the maximum amount:
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// assert_eq!(Amount::from_units_decimals(175, 646), Some(Amount::from_repr(175_646)));
+    /// assert_eq!(Amount::from_units_decimals(175, 1_000), None);
+    /// ```
+    pub fn from_units_decimals(units: u64, millis: u16) -> Option<Amount> {
+        if millis >= 1_000 {
+            None
+        } else {
+            units.checked_mul(1_000)
+                .and_then(|v| v.checked_add(millis as u64))
+                .map(Amount::from_repr)
+        }
+    }
+
+    /// Creates a zero amount.
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// assert_eq!(Amount::zero(), Amount::from_repr(0));
+    /// ```
+    pub fn zero() -> Amount {
+        Amount { value: 0 }
+    }
+
+    /// Checks if the amount is zero.
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// assert!(Amount::zero().is_zero());
+    /// assert!(!Amount::from_repr(1).is_zero());
+    /// ```
+    pub fn is_zero(&self) -> bool {
+        self.value == 0
+    }
+
     /// Returns the smallest value that can be represented as a currency amount.
     pub fn min_value() -> Amount {
         Amount { value: u64::MIN }
@@ -121,6 +227,1096 @@ impl Amount {
     pub fn max_value() -> Amount {
         Amount { value: u64::MAX }
     }
+
+    /// Adds two amounts, clamping the sum to the maximum amount instead of overflowing.
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let amount = Amount::from_repr(5);
+    /// assert_eq!(amount.saturating_add(Amount::from_repr(10)), Amount::from_repr(15));
+    /// assert_eq!(Amount::max_value().saturating_add(amount), Amount::max_value());
+    /// ```
+    pub fn saturating_add(&self, rhs: Amount) -> Amount {
+        Amount { value: self.value.saturating_add(rhs.value) }
+    }
+
+    /// Subtracts an amount, clamping the result to the minimum amount instead of underflowing.
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let amount = Amount::from_repr(5);
+    /// assert_eq!(amount.saturating_sub(Amount::from_repr(2)), Amount::from_repr(3));
+    /// assert_eq!(amount.saturating_sub(Amount::from_repr(10)), Amount::min_value());
+    /// ```
+    pub fn saturating_sub(&self, rhs: Amount) -> Amount {
+        Amount { value: self.value.saturating_sub(rhs.value) }
+    }
+
+    /// Multiplies the amount by an integer, clamping the product to the maximum amount instead of
+    /// overflowing.
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let amount = Amount::from_repr(5);
+    /// assert_eq!(amount.saturating_mul(3), Amount::from_repr(15));
+    /// assert_eq!(Amount::max_value().saturating_mul(2), Amount::max_value());
+    /// ```
+    pub fn saturating_mul(&self, rhs: u64) -> Amount {
+        Amount { value: self.value.saturating_mul(rhs) }
+    }
+
+    /// Parses an amount from a string.
+    ///
+    /// This is the same as the `FromStr` implementation, but it does not require importing the
+    /// `FromStr` trait:
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// assert_eq!(Amount::parse("175.646").unwrap(), Amount::from_repr(175_646));
+    /// ```
+    pub fn parse(s: &str) -> Result<Amount, AmountParseError> {
+        s.parse()
+    }
+
+    /// Parses an amount, accepting a lone decimal separator as zero.
+    ///
+    /// Some spreadsheet exports write zero amounts as `.`, which `FromStr` rejects, since it has
+    /// neither units nor decimals. Any other string is parsed as with `FromStr`:
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// assert_eq!(Amount::from_str_lenient(".").unwrap(), Amount::min_value());
+    /// assert!(".".parse::<Amount>().is_err());
+    /// ```
+    pub fn from_str_lenient(s: &str) -> Result<Amount, AmountParseError> {
+        if s == "." {
+            Ok(Amount::min_value())
+        } else {
+            s.parse()
+        }
+    }
+
+    /// Parses an amount, rejecting superfluous leading zeros in the units part.
+    ///
+    /// Strings such as `0175.6` are accepted by `FromStr`, but some systems consider them
+    /// suspicious. The zeros are looked for after the currency symbol and the sign, if any. A
+    /// single zero before the decimal separator is still accepted:
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// assert_eq!(Amount::from_str_strict("0.5").unwrap(), Amount::from_repr(500));
+    /// assert!(Amount::from_str_strict("0175.6").is_err());
+    /// assert!(Amount::from_str_strict("₣ 0175.6").is_err());
+    /// assert_eq!("0175.6".parse::<Amount>().unwrap(), Amount::from_repr(175_600));
+    /// ```
+    pub fn from_str_strict(s: &str) -> Result<Amount, AmountParseError> {
+        parse_amount(s, true)
+    }
+
+    /// Checks if the decimal part of the given amount string has more than three significant
+    /// digits.
+    ///
+    /// Amounts only have three decimals, so parsing such strings rounds them. This allows user
+    /// interfaces to warn about it before parsing. Trailing zeros are not significant, and the
+    /// string is not validated otherwise:
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// assert!(Amount::str_has_excess_precision("175.6465"));
+    /// assert!(!Amount::str_has_excess_precision("175.6460"));
+    /// assert!(!Amount::str_has_excess_precision("175.64"));
+    /// ```
+    pub fn str_has_excess_precision(s: &str) -> bool {
+        match s.find('.') {
+            Some(p) => s[p + 1..].trim_end_matches('0').len() > 3,
+            None => false,
+        }
+    }
+
+    /// Parses an amount that might be followed by a unit suffix.
+    ///
+    /// Configuration files sometimes write amounts along with their unit. This accepts the same
+    /// amounts as `FromStr`, optionally followed by the `CURRENCY_SYMBOL` or the `credit` or
+    /// `credits` words (in any case), with or without whitespace in between. Any other suffix is
+    /// an error.
+    ///
+    /// ```
+    /// use fractal_utils::{Amount, CURRENCY_SYMBOL};
+    ///
+    /// let amount = Amount::from_str_with_unit("175.646 credits").unwrap();
+    /// assert_eq!(amount, Amount::from_repr(175_646));
+    ///
+    /// let amount = Amount::from_str_with_unit(&format!("175.646{}", CURRENCY_SYMBOL)).unwrap();
+    /// assert_eq!(amount, Amount::from_repr(175_646));
+    ///
+    /// assert!(Amount::from_str_with_unit("175.646 dollars").is_err());
+    /// ```
+    pub fn from_str_with_unit(s: &str) -> Result<Amount, AmountParseError> {
+        let trimmed = s.trim();
+        let mut symbol = [0u8; 4];
+        let symbol: &str = CURRENCY_SYMBOL.encode_utf8(&mut symbol);
+        // The known units are stripped from the end, and the rest is parsed as with `FromStr`.
+        // `credits` is checked before `credit`, since it ends with it too.
+        let has_unit = |unit: &str| match trimmed.len().checked_sub(unit.len()) {
+            Some(p) => trimmed.is_char_boundary(p) && trimmed[p..].eq_ignore_ascii_case(unit),
+            None => false,
+        };
+        let unit_len = [symbol, "credits", "credit"]
+            .iter()
+            .find(|unit| has_unit(unit))
+            .map_or(0, |unit| unit.len());
+        let number = trimmed[..trimmed.len() - unit_len].trim_end();
+
+        // Any other suffix that can't be part of an amount is an unknown unit.
+        let in_number = |c: char| c.is_digit(10) || c == '.' || c == ',' || c.is_whitespace();
+        let unit = &number[number.trim_end_matches(|c: char| !in_number(c)).len()..];
+        if unit_len == 0 && !unit.is_empty() {
+            Err(AmountParseError::new(s,
+                                      &format!("the unit {:?} is not a known Fractal Global \
+                                                Credits unit",
+                                               unit),
+                                      None))
+        } else {
+            number.parse()
+        }
+    }
+
+    /// Computes a fee over the amount, with a minimum fee.
+    ///
+    /// The fee is computed as a rate in basis points (1/100 of a percent) of the amount, rounded
+    /// half up to the nearest thousandth, and it will always be at least the given `minimum`.
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let minimum = Amount::from_repr(100); // 0.1
+    /// // A 2% fee:
+    /// assert_eq!(Amount::from_repr(50_000).fee(200, minimum), Amount::from_repr(1_000));
+    /// assert_eq!(Amount::from_repr(2_000).fee(200, minimum), minimum);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// It will panic if the fee is bigger than the maximum amount, which can only happen with
+    /// rates over 100%.
+    pub fn fee(&self, rate_bps: u32, minimum: Amount) -> Amount {
+        let fee = round_div(self.value as u128 * rate_bps as u128,
+                            10_000,
+                            RoundingMode::HalfUp);
+        assert!(fee <= u64::MAX as u128, "the fee is too big to be represented as an amount");
+        let fee = Amount::from_repr(fee as u64);
+        if fee < minimum { minimum } else { fee }
+    }
+
+    /// Splits the amount proportionally to the given weights.
+    ///
+    /// Each share is computed by truncating its exact proportional part to thousandths, and the
+    /// thousandths that are left are assigned one by one to the shares with the largest truncated
+    /// remainders (the first ones in case of a tie). This makes the shares always sum exactly to
+    /// the allocated amount.
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let shares = Amount::from_repr(100).allocate(&[1, 1, 1]);
+    /// assert_eq!(shares[0], Amount::from_repr(34));
+    /// assert_eq!(shares[1], Amount::from_repr(33));
+    /// assert_eq!(shares[2], Amount::from_repr(33));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// It will panic if the amount is not zero and there are no weights or all of them are zero,
+    /// since it can't be allocated.
+    pub fn allocate(&self, weights: &[u64]) -> Vec<Amount> {
+        let total_weight = weights.iter().fold(0u128, |acc, w| acc + *w as u128);
+        if total_weight == 0 {
+            assert_eq!(self.value,
+                       0,
+                       "a non-zero amount can't be allocated without non-zero weights");
+            return vec![Amount::min_value(); weights.len()];
+        }
+
+        let mut shares = Vec::with_capacity(weights.len());
+        let mut remainders = Vec::with_capacity(weights.len());
+        let mut allocated = 0u64;
+        for (i, weight) in weights.iter().enumerate() {
+            let exact = self.value as u128 * *weight as u128;
+            // Each share is at most the total amount, so it fits in a `u64`.
+            let share = (exact / total_weight) as u64;
+            allocated += share;
+            shares.push(Amount::from_repr(share));
+            remainders.push((exact % total_weight, i));
+        }
+
+        // Largest remainders first, and the first recipients in case of a tie.
+        remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        for &(_, i) in remainders.iter().take((self.value - allocated) as usize) {
+            shares[i].value += 1;
+        }
+        shares
+    }
+
+    /// Allocates the amount filling each bucket up to its cap, in order.
+    ///
+    /// It returns the allocations, in the same order as the caps, and the remainder that could
+    /// not be allocated because the amount exceeds the sum of the caps:
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let caps = [Amount::from_repr(50_000), Amount::from_repr(30_000)];
+    /// let (allocations, remainder) = Amount::from_repr(60_000).allocate_capped(&caps);
+    /// assert_eq!(allocations, vec![Amount::from_repr(50_000), Amount::from_repr(10_000)]);
+    /// assert_eq!(remainder, Amount::min_value());
+    /// ```
+    pub fn allocate_capped(&self, caps: &[Amount]) -> (Vec<Amount>, Amount) {
+        let mut remaining = self.value;
+        let allocations = caps.iter()
+            .map(|cap| {
+                let allocation = cmp::min(remaining, cap.value);
+                remaining -= allocation;
+                Amount::from_repr(allocation)
+            })
+            .collect();
+        (allocations, Amount::from_repr(remaining))
+    }
+
+    /// Breaks the amount into counts of the given denominations, as in a cash drawer.
+    ///
+    /// The amount is decomposed greedily: it takes as many of the largest denomination as
+    /// possible, then of the next one, and so on. It returns each denomination along with its
+    /// count, from the largest to the smallest. Zero denominations get a count of zero. If the
+    /// denominations can't decompose the whole amount, the leftover is not returned, it's what
+    /// remains after subtracting the counted denominations:
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let denominations = [Amount::from_repr(5_000), Amount::from_repr(20_000)];
+    /// let breakdown = Amount::from_repr(47_500).break_into(&denominations);
+    /// assert_eq!(breakdown,
+    ///            vec![(Amount::from_repr(20_000), 2), (Amount::from_repr(5_000), 1)]);
+    /// // 2.5 credits are left over.
+    /// ```
+    pub fn break_into(&self, denominations: &[Amount]) -> Vec<(Amount, u64)> {
+        let mut sorted = denominations.to_vec();
+        Amount::sort_descending(&mut sorted);
+        let mut remaining = self.value;
+        sorted.into_iter()
+            .map(|denomination| {
+                let count = remaining.checked_div(denomination.value).unwrap_or(0);
+                remaining -= count * denomination.value;
+                (denomination, count)
+            })
+            .collect()
+    }
+
+    /// Compounds the amount at the given rate for the given number of periods.
+    ///
+    /// In each period, the interest at the rate in basis points (1/100 of a percent) is computed
+    /// over the current amount, rounded half up to the nearest thousandth, and added to it. It
+    /// returns `None` if the result doesn't fit in an `Amount`.
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let amount = Amount::from_repr(1_000_000); // 1,000
+    /// // 5% over 2 periods:
+    /// assert_eq!(amount.compound(500, 2), Some(Amount::from_repr(1_102_500)));
+    /// assert_eq!(Amount::max_value().compound(500, 2), None);
+    /// ```
+    pub fn compound(&self, rate_bps: u32, periods: u32) -> Option<Amount> {
+        let mut value = self.value as u128;
+        for _ in 0..periods {
+            value += round_div(value * rate_bps as u128, 10_000, RoundingMode::HalfUp);
+            if value > u64::MAX as u128 {
+                return None;
+            }
+        }
+        Some(Amount::from_repr(value as u64))
+    }
+
+    /// Divides the amount by another amount, returning the quotient and the remainder.
+    ///
+    /// The quotient is the number of whole times the divisor fits in the amount, and the remainder
+    /// is the amount that is left. It returns `None` if the divisor is zero.
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let amount = Amount::from_repr(10_500); // 10.5
+    /// assert_eq!(amount.div_rem(&Amount::from_repr(3_000)), Some((3, Amount::from_repr(1_500))));
+    /// assert_eq!(amount.div_rem(&Amount::min_value()), None);
+    /// ```
+    pub fn div_rem(&self, divisor: &Amount) -> Option<(u64, Amount)> {
+        if divisor.value == 0 {
+            None
+        } else {
+            Some((self.value / divisor.value, Amount::from_repr(self.value % divisor.value)))
+        }
+    }
+
+    /// Parses an amount from an integer string of base units at the given scale.
+    ///
+    /// Some systems represent amounts as arbitrary precision integers of base units, where each
+    /// unit is `10^-scale` credits. This rescales them to thousandths, rounding half up when the
+    /// scale is bigger than 3. It returns an error if the string is not an integer or if the
+    /// amount is too big to be represented.
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// // 175.646 with 18 decimals:
+    /// let amount = Amount::from_base_units_str("175646000000000000000", 18).unwrap();
+    /// assert_eq!(amount, Amount::from_repr(175_646));
+    ///
+    /// let amount = Amount::from_base_units_str("17565", 2).unwrap();
+    /// assert_eq!(amount, Amount::from_repr(175_650));
+    /// ```
+    pub fn from_base_units_str(s: &str, scale: u32) -> Result<Amount, AmountParseError> {
+        if s.is_empty() {
+            return Err(AmountParseError::new(s, "no base units were found", None));
+        }
+        if let Some(p) = first_non_digit(s) {
+            return Err(AmountParseError::new(s, "it is not a valid base units integer", None)
+                .at(Some(p)));
+        }
+
+        // The digits are handled as a string, so that any number of them can be rescaled.
+        // Digits that don't fit in an `u128` can't fit in an amount either.
+        let parse = |digits: &str| if digits.is_empty() {
+            Some(0)
+        } else {
+            digits.parse::<u128>().ok()
+        };
+        let digits = s.trim_start_matches('0');
+        let repr = if scale <= 3 {
+            parse(digits).and_then(|v| v.checked_mul(10u128.pow(3 - scale)))
+        } else {
+            let dropped = (scale - 3) as usize;
+            if digits.len() < dropped {
+                Some(0)
+            } else {
+                let (kept, rest) = digits.split_at(digits.len() - dropped);
+                let round_up = rest.bytes().next().map_or(false, |d| d >= b'5');
+                parse(kept).and_then(|v| if round_up { v.checked_add(1) } else { Some(v) })
+            }
+        };
+
+        match repr {
+            Some(r) if r <= u64::MAX as u128 => Ok(Amount::from_repr(r as u64)),
+            _ => Err(AmountParseError::too_big(s)),
+        }
+    }
+
+    /// Parses an amount, with an implied scale for strings without decimal separator.
+    ///
+    /// Strings with a decimal separator are parsed as with `FromStr`. Strings without one are
+    /// considered integers of base units with `implied_scale` decimals, as in
+    /// `from_base_units_str()`, which is useful for importers with fixed point exports:
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let amount = Amount::from_str_with_scale("175646", 3).unwrap();
+    /// assert_eq!(amount, Amount::from_repr(175_646));
+    ///
+    /// let amount = Amount::from_str_with_scale("175.646", 2).unwrap();
+    /// assert_eq!(amount, Amount::from_repr(175_646));
+    /// ```
+    pub fn from_str_with_scale(s: &str, implied_scale: u32) -> Result<Amount, AmountParseError> {
+        if s.contains('.') {
+            s.parse()
+        } else {
+            Amount::from_base_units_str(s, implied_scale)
+        }
+    }
+
+    /// Formats the amount along with a currency symbol.
+    ///
+    /// The symbol can be placed before or after the amount, optionally separated by a space. The
+    /// amount is formatted as with the `Display` trait. Usually, the symbol will be the
+    /// `CURRENCY_SYMBOL`:
+    ///
+    /// ```
+    /// use fractal_utils::{Amount, CURRENCY_SYMBOL};
+    ///
+    /// let amount = Amount::from_repr(175_646);
+    /// assert_eq!(amount.format_currency(CURRENCY_SYMBOL, true, true), "₣ 175.646");
+    /// assert_eq!(amount.format_currency(CURRENCY_SYMBOL, false, false), "175.646₣");
+    /// ```
+    pub fn format_currency(&self, symbol: char, symbol_before: bool, space: bool) -> String {
+        let separator = if space { " " } else { "" };
+        if symbol_before {
+            format!("{}{}{}", symbol, separator, self)
+        } else {
+            format!("{}{}{}", self, separator, symbol)
+        }
+    }
+
+    /// Formats the amount with at least the given number of integer digits.
+    ///
+    /// Unlike the width of the formatter, which pads the whole string, this only pads the units
+    /// with leading zeros, and the decimals are shown as in the `Display` implementation:
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let amount = Amount::from_repr(5_500); // 5.5
+    /// assert_eq!(amount.format_min_int_digits(2), "05.5");
+    /// ```
+    pub fn format_min_int_digits(&self, n: usize) -> String {
+        let formatted = self.to_string();
+        let int_digits = formatted.find('.').unwrap_or(formatted.len());
+        if int_digits >= n {
+            formatted
+        } else {
+            format!("{:0>2$}{}", "", formatted, n - int_digits)
+        }
+    }
+
+    /// Writes the amount in English words, as printed in checks.
+    ///
+    /// The units are written in words, and the decimals as a fraction of a thousand:
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let amount = Amount::from_repr(175_646);
+    /// assert_eq!(amount.to_words(), "one hundred seventy-five and 646/1000");
+    /// assert_eq!(Amount::min_value().to_words(), "zero and 000/1000");
+    /// ```
+    pub fn to_words(&self) -> String {
+        let mut units = self.value / 1_000;
+        let words = if units == 0 {
+            String::from(SMALL_NUMBER_WORDS[0])
+        } else {
+            let mut groups = Vec::new();
+            let mut scale = 0;
+            while units > 0 {
+                let group = units % 1_000;
+                if group > 0 {
+                    let words = hundreds_to_words(group);
+                    groups.push(if scale == 0 {
+                        words
+                    } else {
+                        format!("{} {}", words, SCALE_WORDS[scale])
+                    });
+                }
+                units /= 1_000;
+                scale += 1;
+            }
+            groups.reverse();
+            groups.join(" ")
+        };
+        format!("{} and {:03}/1000", words, self.value % 1_000)
+    }
+
+    /// Parses an amount written in English words, as written by `to_words()`.
+    ///
+    /// The decimals can be given as a fraction of a thousand after the units, separated by
+    /// `and`. Hyphens between tens and ones are optional, and the words are case insensitive:
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let amount = Amount::from_words("one hundred seventy-five and 646/1000").unwrap();
+    /// assert_eq!(amount, Amount::from_repr(175_646));
+    ///
+    /// let amount = Amount::from_words("Two Thousand Forty").unwrap();
+    /// assert_eq!(amount, Amount::from_repr(2_040_000));
+    /// ```
+    pub fn from_words(s: &str) -> Result<Amount, AmountParseError> {
+        let lowercase = s.trim().to_lowercase();
+        let (words, decimals) = match lowercase.rfind(" and ") {
+            Some(p) => {
+                let fraction = &lowercase[p + 5..];
+                let numerator = if fraction.ends_with("/1000") {
+                    &fraction[..fraction.len() - 5]
+                } else {
+                    return Err(AmountParseError::new(s,
+                                                     "the fraction is not a fraction of 1000",
+                                                     None));
+                };
+                if numerator.is_empty() || numerator.len() > 3 ||
+                   first_non_digit(numerator).is_some() {
+                    return Err(AmountParseError::new(s,
+                                                     "the numerator of the fraction is not a \
+                                                      number between 0 and 999",
+                                                     None));
+                }
+                (&lowercase[..p], numerator.parse::<u64>().unwrap())
+            }
+            None => (lowercase.as_str(), 0),
+        };
+
+        // The kind of the last word of the current group, to reject words in the wrong order,
+        // such as `five five` or `five twenty`.
+        #[derive(Copy, Clone, PartialEq)]
+        enum Word {
+            Ones,
+            Teen,
+            Tens,
+            Hundred,
+        }
+        let misplaced = |s: &str, word: &str| {
+            Err(AmountParseError::new(s, &format!("the number word {:?} is misplaced", word), None))
+        };
+
+        let mut units = 0u64;
+        let mut group = 0u64;
+        let mut last_word = None;
+        let mut last_scale = SCALE_WORDS.len();
+        let mut any_words = false;
+        for word in words.split(|c: char| c.is_whitespace() || c == '-').filter(|w| !w.is_empty()) {
+            any_words = true;
+            if word == "zero" && words.trim() == "zero" {
+                continue;
+            }
+            if let Some(n) = SMALL_NUMBER_WORDS.iter().skip(1).position(|&w| w == word) {
+                let n = n as u64 + 1;
+                let kind = if n < 10 { Word::Ones } else { Word::Teen };
+                match last_word {
+                    Some(Word::Ones) | Some(Word::Teen) => return misplaced(s, word),
+                    Some(Word::Tens) if kind == Word::Teen => return misplaced(s, word),
+                    _ => {}
+                }
+                group += n;
+                last_word = Some(kind);
+            } else if let Some(n) = TENS_WORDS.iter().skip(2).position(|&w| w == word) {
+                if last_word.is_some() && last_word != Some(Word::Hundred) {
+                    return misplaced(s, word);
+                }
+                group += (n as u64 + 2) * 10;
+                last_word = Some(Word::Tens);
+            } else if word == "hundred" {
+                if last_word != Some(Word::Ones) || group >= 10 {
+                    return misplaced(s, word);
+                }
+                group *= 100;
+                last_word = Some(Word::Hundred);
+            } else if let Some(scale) = SCALE_WORDS.iter().skip(1).position(|&w| w == word) {
+                let scale = scale + 1;
+                if group == 0 || group > 999 || scale >= last_scale {
+                    return Err(AmountParseError::new(s, "the scale words are misplaced", None));
+                }
+                units = match group.checked_mul(1_000u64.pow(scale as u32))
+                    .and_then(|v| v.checked_add(units)) {
+                    Some(u) => u,
+                    None => return Err(AmountParseError::too_big(s)),
+                };
+                group = 0;
+                last_word = None;
+                last_scale = scale;
+            } else {
+                return Err(AmountParseError::new(s,
+                                                 &format!("{:?} is not a valid number word", word),
+                                                 None));
+            }
+        }
+        if !any_words {
+            return Err(AmountParseError::new(s, "no number words were found", None));
+        }
+
+        match units.checked_add(group)
+            .and_then(|u| u.checked_mul(1_000))
+            .and_then(|r| r.checked_add(decimals)) {
+            Some(r) => Ok(Amount::from_repr(r)),
+            None => Err(AmountParseError::too_big(s)),
+        }
+    }
+
+    /// Checks if the amount is *dust*: a non-zero amount smaller than the given threshold.
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let threshold = Amount::from_repr(10); // 0.01
+    /// assert!(Amount::from_repr(5).is_dust(threshold));
+    /// assert!(!Amount::min_value().is_dust(threshold));
+    /// assert!(!Amount::from_repr(10).is_dust(threshold));
+    /// ```
+    pub fn is_dust(&self, threshold: Amount) -> bool {
+        self.value != 0 && *self < threshold
+    }
+
+    /// Converts the amount to a float.
+    ///
+    /// This is useful for statistics or plots, but floats only have 53 bits of precision, so
+    /// amounts bigger than about 9 trillion credits lose their last decimals. It should never be
+    /// used for balances:
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// assert_eq!(Amount::from_repr(175_500).to_f64(), 175.5);
+    /// ```
+    pub fn to_f64(&self) -> f64 {
+        self.value as f64 / 1_000.0
+    }
+
+    /// Computes the percentage change of the amount from the given baseline.
+    ///
+    /// The result is negative if the amount is smaller than the baseline, and `None` if the
+    /// baseline is zero. It's computed with floating point numbers, so it's meant for analytics,
+    /// not for balances:
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let baseline = Amount::from_repr(200_000);
+    /// assert_eq!(Amount::from_repr(250_000).percent_change_from(&baseline), Some(25.0));
+    /// assert_eq!(Amount::from_repr(150_000).percent_change_from(&baseline), Some(-25.0));
+    /// assert_eq!(baseline.percent_change_from(&Amount::min_value()), None);
+    /// ```
+    pub fn percent_change_from(&self, baseline: &Amount) -> Option<f64> {
+        if baseline.value == 0 {
+            None
+        } else {
+            Some((self.value as f64 - baseline.value as f64) / baseline.value as f64 * 100.0)
+        }
+    }
+
+    /// Compares two amounts in descending order.
+    ///
+    /// This is the reverse of the `Ord` implementation, so that the largest amounts come first
+    /// when used for sorting:
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let mut amounts = vec![Amount::from_repr(5_000), Amount::from_repr(10_000)];
+    /// amounts.sort_by(Amount::cmp_desc);
+    /// assert_eq!(amounts, vec![Amount::from_repr(10_000), Amount::from_repr(5_000)]);
+    /// ```
+    pub fn cmp_desc(&self, other: &Amount) -> cmp::Ordering {
+        other.cmp(self)
+    }
+
+    /// Sorts the given amounts in descending order, the largest first.
+    pub fn sort_descending(amounts: &mut [Amount]) {
+        amounts.sort_by(Amount::cmp_desc);
+    }
+
+    /// Divides the amount by an integer, tracking if the division loses precision.
+    ///
+    /// The result is truncated as with the `Div` implementation, and it's marked as lossy if the
+    /// division has a non-zero remainder:
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let result = Amount::from_repr(10_000).div_tracked(4);
+    /// assert_eq!(result.amount, Amount::from_repr(2_500));
+    /// assert!(!result.lossy);
+    ///
+    /// let result = Amount::from_repr(10_000).div_tracked(3);
+    /// assert_eq!(result.amount, Amount::from_repr(3_333));
+    /// assert!(result.lossy);
+    /// ```
+    ///
+    /// It will panic if `rhs` is zero.
+    pub fn div_tracked(&self, rhs: u64) -> LossyAmount {
+        LossyAmount {
+            amount: Amount::from_repr(self.value / rhs),
+            lossy: self.value % rhs != 0,
+        }
+    }
+
+    /// Divides the amount by an integer, rounding the last thousandth with the given mode.
+    ///
+    /// Unlike the `Div` implementation, which truncates, the quotient is rounded. It returns
+    /// `None` if `rhs` is zero:
+    ///
+    /// ```
+    /// use fractal_utils::{Amount, RoundingMode};
+    ///
+    /// let amount = Amount::from_repr(20_000);
+    /// assert_eq!(amount / 3u64, Amount::from_repr(6_666));
+    /// assert_eq!(amount.div_rounded(3, RoundingMode::HalfUp), Some(Amount::from_repr(6_667)));
+    /// assert_eq!(amount.div_rounded(0, RoundingMode::HalfUp), None);
+    /// ```
+    pub fn div_rounded(&self, rhs: u64, mode: RoundingMode) -> Option<Amount> {
+        if rhs == 0 {
+            None
+        } else {
+            // The rounded quotient is never bigger than the amount, so it always fits in a `u64`.
+            Some(Amount::from_repr(round_div(self.value as u128, rhs as u128, mode) as u64))
+        }
+    }
+
+    /// Wrapping addition: adds two amounts, wrapping around at the maximum amount.
+    ///
+    /// **Note:** wrapping operations are meant for simulations and tests that need modular
+    /// arithmetic. They should never be used with real balances, since an overflow silently
+    /// produces a wrong amount.
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let amount = Amount::max_value().wrapping_add(Amount::from_repr(1));
+    /// assert_eq!(amount, Amount::min_value());
+    /// ```
+    pub fn wrapping_add(&self, rhs: Amount) -> Amount {
+        Amount::from_repr(self.value.wrapping_add(rhs.value))
+    }
+
+    /// Wrapping subtraction: substracts two amounts, wrapping around at the minimum amount.
+    ///
+    /// **Note:** as with `wrapping_add()`, this should never be used with real balances.
+    pub fn wrapping_sub(&self, rhs: Amount) -> Amount {
+        Amount::from_repr(self.value.wrapping_sub(rhs.value))
+    }
+
+    /// Wrapping multiplication: multiplies the amount by an integer, wrapping around at the
+    /// maximum amount.
+    ///
+    /// **Note:** as with `wrapping_add()`, this should never be used with real balances.
+    pub fn wrapping_mul(&self, rhs: u64) -> Amount {
+        Amount::from_repr(self.value.wrapping_mul(rhs))
+    }
+
+    /// Reconciles a statement, computing the balance of the given credits minus the given debits.
+    ///
+    /// Both sides are summed checking for overflows, and it returns an error if any of the sums
+    /// overflows or if the debits exceed the credits.
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let credits = [Amount::from_repr(10_000), Amount::from_repr(5_500)];
+    /// let debits = [Amount::from_repr(3_000)];
+    /// assert_eq!(Amount::reconcile(&credits, &debits), Ok(Amount::from_repr(12_500)));
+    /// assert!(Amount::reconcile(&debits, &credits).is_err());
+    /// ```
+    pub fn reconcile(credits: &[Amount], debits: &[Amount]) -> Result<Amount, ReconcileError> {
+        let sum = |amounts: &[Amount]| {
+            amounts.iter().fold(Some(0u64), |acc, a| acc.and_then(|s| s.checked_add(a.value)))
+        };
+        let credits = sum(credits).ok_or(ReconcileError::Overflow)?;
+        let debits = sum(debits).ok_or(ReconcileError::Overflow)?;
+        if debits > credits {
+            Err(ReconcileError::InsufficientCredits {
+                credits: Amount::from_repr(credits),
+                debits: Amount::from_repr(debits),
+            })
+        } else {
+            Ok(Amount::from_repr(credits - debits))
+        }
+    }
+
+    /// Computes the change of a payment, the paid amount minus the due amount.
+    ///
+    /// It returns an error with both amounts if the paid amount is smaller than the due amount:
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let due = Amount::from_repr(175_646);
+    /// assert_eq!(Amount::change(due, Amount::from_repr(200_000)),
+    ///            Ok(Amount::from_repr(24_354)));
+    /// assert!(Amount::change(due, Amount::from_repr(100_000)).is_err());
+    /// ```
+    pub fn change(due: Amount, paid: Amount) -> Result<Amount, InsufficientFunds> {
+        paid.checked_sub(due).ok_or(InsufficientFunds {
+            due: due,
+            paid: paid,
+        })
+    }
+
+    /// Sums the given amounts, returning the index of the amount that overflows the sum, if any.
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let amounts = [Amount::from_repr(10_000), Amount::from_repr(5_500)];
+    /// assert_eq!(Amount::checked_running_sum(&amounts), Ok(Amount::from_repr(15_500)));
+    ///
+    /// let amounts = [Amount::from_repr(1), Amount::max_value(), Amount::from_repr(1)];
+    /// assert_eq!(Amount::checked_running_sum(&amounts), Err(1));
+    /// ```
+    pub fn checked_running_sum(amounts: &[Amount]) -> Result<Amount, usize> {
+        let mut sum = 0u64;
+        for (i, amount) in amounts.iter().enumerate() {
+            sum = sum.checked_add(amount.value).ok_or(i)?;
+        }
+        Ok(Amount::from_repr(sum))
+    }
+
+    /// Adds two amounts, returning `None` if the sum overflows the maximum amount.
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let amount = Amount::from_repr(175_646);
+    /// assert_eq!(amount.checked_add(Amount::from_repr(354)), Some(Amount::from_repr(176_000)));
+    /// assert_eq!(Amount::max_value().checked_add(Amount::from_repr(1)), None);
+    /// ```
+    pub fn checked_add(&self, rhs: Amount) -> Option<Amount> {
+        self.value.checked_add(rhs.value).map(Amount::from_repr)
+    }
+
+    /// Subtracts an amount, returning `None` if the result would be negative.
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let amount = Amount::from_repr(175_646);
+    /// assert_eq!(amount.checked_sub(Amount::from_repr(646)), Some(Amount::from_repr(175_000)));
+    /// assert_eq!(Amount::min_value().checked_sub(Amount::from_repr(1)), None);
+    /// ```
+    pub fn checked_sub(&self, rhs: Amount) -> Option<Amount> {
+        self.value.checked_sub(rhs.value).map(Amount::from_repr)
+    }
+
+    /// Multiplies the amount by an integer, returning `None` if the product overflows the maximum
+    /// amount.
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let amount = Amount::from_repr(175_646);
+    /// assert_eq!(amount.checked_mul(2), Some(Amount::from_repr(351_292)));
+    /// assert_eq!(Amount::max_value().checked_mul(2), None);
+    /// ```
+    pub fn checked_mul(&self, rhs: u64) -> Option<Amount> {
+        self.value.checked_mul(rhs).map(Amount::from_repr)
+    }
+
+    /// Divides the amount by an integer, returning `None` if the divisor is zero.
+    ///
+    /// As with the `Div` implementations, the decimals that don't fit in the precision of the
+    /// amount are truncated:
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let amount = Amount::from_repr(175_646);
+    /// assert_eq!(amount.checked_div(2), Some(Amount::from_repr(87_823)));
+    /// assert_eq!(amount.checked_div(0), None);
+    /// ```
+    pub fn checked_div(&self, rhs: u64) -> Option<Amount> {
+        self.value.checked_div(rhs).map(Amount::from_repr)
+    }
+
+    /// Divides the amount by an integer of any unsigned type, returning `None` if it's zero.
+    ///
+    /// The result is the same as with the `Div` implementations, which panic if the divisor is
+    /// zero:
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let amount = Amount::from_repr(10_000);
+    /// assert_eq!(amount.checked_div_int(3u32), Some(amount / 3u32));
+    /// assert_eq!(amount.checked_div_int(0u32), None);
+    /// ```
+    pub fn checked_div_int<T: IntDivisor>(&self, rhs: T) -> Option<Amount> {
+        self.checked_div(rhs.to_u64())
+    }
+
+    /// Computes the remainder of the amount divided by an integer number of credits, returning
+    /// `None` if it's zero.
+    ///
+    /// The result is the same as with the `Rem` implementations, which panic if the divisor is
+    /// zero:
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let amount = Amount::from_repr(12_345);
+    /// assert_eq!(amount.checked_rem_int(10u32), Some(amount % 10u32));
+    /// assert_eq!(amount.checked_rem_int(0u32), None);
+    /// ```
+    pub fn checked_rem_int<T: IntDivisor>(&self, rhs: T) -> Option<Amount> {
+        let rhs = rhs.to_u64();
+        if rhs == 0 {
+            None
+        } else {
+            // Divisors too big to be multiplied by 1,000 are bigger than any amount.
+            Some(Amount::from_repr(rhs.checked_mul(1_000).map_or(self.value, |d| self.value % d)))
+        }
+    }
+
+    /// Multiplies the amount by each of the given factors, returning `None` on overflow.
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let amount = Amount::from_repr(1_500);
+    /// assert_eq!(amount.scale_by_all(&[2, 3, 10]), Some(Amount::from_repr(90_000)));
+    /// assert_eq!(amount.scale_by_all(&[]), Some(amount));
+    /// assert_eq!(Amount::max_value().scale_by_all(&[1, 2]), None);
+    /// ```
+    pub fn scale_by_all(&self, factors: &[u64]) -> Option<Amount> {
+        factors.iter().fold(Some(*self), |acc, &factor| acc.and_then(|a| a.checked_mul(factor)))
+    }
+
+    /// Converts the amount to minor units, hundredths of a credit, rounding with the given mode.
+    ///
+    /// ```
+    /// use fractal_utils::{Amount, RoundingMode};
+    ///
+    /// let amount = Amount::from_repr(175_646); // 175.646
+    /// assert_eq!(amount.to_minor_units(RoundingMode::HalfUp), 17_565);
+    /// ```
+    pub fn to_minor_units(&self, mode: RoundingMode) -> u64 {
+        self.to_iso_minor_units(2, mode)
+    }
+
+    /// Converts the amount to the minor units of a currency with the given ISO 4217 exponent.
+    ///
+    /// The exponent is the number of decimals of the currency: `0` for currencies without minor
+    /// units, `2` for cents and `3` for thousandths, which is the precision of amounts. Smaller
+    /// exponents round with the given mode:
+    ///
+    /// ```
+    /// use fractal_utils::{Amount, RoundingMode};
+    ///
+    /// let amount = Amount::from_repr(175_646); // 175.646
+    /// assert_eq!(amount.to_iso_minor_units(0, RoundingMode::HalfUp), 176);
+    /// assert_eq!(amount.to_iso_minor_units(2, RoundingMode::HalfUp), 17_565);
+    /// assert_eq!(amount.to_iso_minor_units(3, RoundingMode::HalfUp), 175_646);
+    /// assert_eq!(amount.to_iso_minor_units(4, RoundingMode::HalfUp), 1_756_460);
+    /// ```
+    ///
+    /// It will panic if the exponent is bigger than 3 and the result does not fit in a `u64`.
+    pub fn to_iso_minor_units(&self, exponent: u32, mode: RoundingMode) -> u64 {
+        if exponent <= 3 {
+            round_div(self.value as u128, 10u128.pow(3 - exponent), mode) as u64
+        } else {
+            (3..exponent)
+                .fold(Some(self.value), |value, _| value.and_then(|v| v.checked_mul(10)))
+                .expect("the amount does not fit in the minor units of the given exponent")
+        }
+    }
+
+    /// Converts the amount to an integer of base units with the given number of decimals,
+    /// clamping it to `u64::MAX` if it does not fit.
+    ///
+    /// This is meant for best-effort metrics and displays, where an approximate value is better
+    /// than a panic. Scales smaller than the precision of amounts round half up, as with
+    /// `to_iso_minor_units()`:
+    ///
+    /// ```
+    /// use std::u64;
+    /// use fractal_utils::Amount;
+    ///
+    /// let amount = Amount::from_repr(175_646); // 175.646
+    /// assert_eq!(amount.saturating_to_scaled(2), 17_565);
+    /// assert_eq!(amount.saturating_to_scaled(6), 175_646_000);
+    /// assert_eq!(amount.saturating_to_scaled(18), u64::MAX);
+    /// ```
+    pub fn saturating_to_scaled(&self, scale: u32) -> u64 {
+        if scale <= 3 {
+            return self.to_iso_minor_units(scale, RoundingMode::default());
+        }
+        let mut scaled = self.value;
+        for _ in 3..scale {
+            match scaled.checked_mul(10) {
+                Some(s) => scaled = s,
+                None => return u64::MAX,
+            }
+            if scaled == 0 {
+                break;
+            }
+        }
+        scaled
+    }
+
+    /// Formats the amount as a plain integer of minor units, without decimal separator.
+    ///
+    /// This is the format some legacy systems expect in their exports. The amount is rounded to
+    /// hundredths with the given mode, as `to_minor_units()` does:
+    ///
+    /// ```
+    /// use fractal_utils::{Amount, RoundingMode};
+    ///
+    /// let amount = Amount::from_repr(175_646); // 175.646
+    /// assert_eq!(amount.to_cents_string(RoundingMode::HalfUp), "17565");
+    /// ```
+    pub fn to_cents_string(&self, mode: RoundingMode) -> String {
+        self.to_minor_units(mode).to_string()
+    }
+
+    /// Reads an amount from the given reader, as the 8 big-endian bytes of its representation.
+    ///
+    /// This is meant for binary protocols, and it complements `write_to()`:
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use fractal_utils::Amount;
+    ///
+    /// let mut reader = Cursor::new(vec![0, 0, 0, 0, 0, 0x02, 0xAE, 0x1E]);
+    /// assert_eq!(Amount::read_from(&mut reader).unwrap(), Amount::from_repr(175_646));
+    /// ```
+    ///
+    /// It returns an error if the reader fails or if it does not have 8 bytes left.
+    pub fn read_from<R: io::Read>(r: &mut R) -> io::Result<Amount> {
+        let mut bytes = [0u8; 8];
+        r.read_exact(&mut bytes)?;
+        Ok(Amount::from_repr(bytes.iter().fold(0, |value, byte| value << 8 | *byte as u64)))
+    }
+
+    /// Writes the amount to the given writer, as the 8 big-endian bytes of its representation.
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let mut writer = Vec::new();
+    /// Amount::from_repr(175_646).write_to(&mut writer).unwrap();
+    /// assert_eq!(writer, [0, 0, 0, 0, 0, 0x02, 0xAE, 0x1E]);
+    /// ```
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        let mut bytes = [0u8; 8];
+        for (i, byte) in bytes.iter_mut().rev().enumerate() {
+            *byte = (self.value >> (8 * i)) as u8;
+        }
+        w.write_all(&bytes)
+    }
+
+    /// Returns a displayable version of the amount that rounds with the given rounding mode.
+    ///
+    /// The `Display` implementation of `Amount` rounds half up when the formatting precision is
+    /// smaller than three decimals. This allows using a different rounding mode, for example, for
+    /// financial reports that require banker's rounding:
+    ///
+    /// ```
+    /// use fractal_utils::{Amount, RoundingMode};
+    ///
+    /// let amount = Amount::from_repr(1_625); // 1.625
+    /// assert_eq!(format!("{:.2}", amount), "1.63");
+    /// assert_eq!(format!("{:.2}", amount.display_rounded(RoundingMode::HalfEven)), "1.62");
+    /// ```
+    pub fn display_rounded(&self, mode: RoundingMode) -> RoundedAmount {
+        RoundedAmount {
+            amount: *self,
+            mode: mode,
+        }
+    }
+
+    /// Returns a displayable version of the amount with thousands separators.
+    ///
+    /// It can be combined with the `+` flag, widths and precisions, as any integer:
+    ///
+    /// ```
+    /// use fractal_utils::Amount;
+    ///
+    /// let amount = Amount::from_repr(1_234_560); // 1234.56
+    /// assert_eq!(format!("{}", amount.display_grouped()), "1,234.56");
+    /// assert_eq!(format!("{:+12.1}", amount.display_grouped()), "    +1,234.6");
+    /// ```
+    pub fn display_grouped(&self) -> GroupedAmount {
+        GroupedAmount { amount: *self }
+    }
 }
 
 #[cfg(feature = "json-types")]
@@ -129,85 +1325,348 @@ impl Amount {
 /// unnacuracies when printed. This can be avoided by using the `Display` trait.
 impl json::ToJson for Amount {
     fn to_json(&self) -> json::Json {
-        json::Json::F64(self.value as f64 / 1000.0)
+        json::Json::F64(self.to_f64())
+    }
+}
+
+#[cfg(feature = "schemars")]
+/// The JSON schema of the `Amount` type describes it as it's serialized: an unsigned integer with
+/// its internal representation.
+impl JsonSchema for Amount {
+    fn schema_name() -> String {
+        String::from("Amount")
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        let schema = SchemaObject {
+            metadata: Some(Box::new(Metadata {
+                description: Some(String::from("Fractal Global Credits amount, as its internal \
+                                                representation: the amount multiplied by \
+                                                1,000. An amount of 1.5 is represented as \
+                                                1500.")),
+                ..Default::default()
+            })),
+            instance_type: Some(InstanceType::Integer.into()),
+            format: Some(String::from("uint64")),
+            number: Some(Box::new(NumberValidation {
+                minimum: Some(0.0),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        schema.into()
+    }
+}
+
+/// Rounding mode used when an `Amount` loses precision.
+///
+/// The default mode is `HalfUp`, which is the one used by the `Display` implementation of
+/// `Amount`. Other modes can be used for displaying by wrapping the amount with
+/// `Amount::display_rounded()`:
+///
+/// ```
+/// use fractal_utils::{Amount, RoundingMode};
+///
+/// let amount = Amount::from_repr(2_500); // 2.5
+/// assert_eq!(format!("{:.0}", amount), "3");
+/// assert_eq!(format!("{:.0}", amount.display_rounded(RoundingMode::HalfEven)), "2");
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum RoundingMode {
+    /// Rounds to the nearest value, and midpoints up: `2.5` will become `3`.
+    HalfUp,
+    /// Rounds to the nearest value, and midpoints to the nearest even value: `2.5` will become
+    /// `2`, while `3.5` will become `4`. This is also known as *banker's rounding*.
+    HalfEven,
+    /// Rounds to the nearest value, and midpoints away from zero: `2.5` will become `3`. Since
+    /// amounts are never negative, this is the same as `HalfUp`.
+    HalfAwayFromZero,
+    /// Rounds to the nearest value, and midpoints towards zero: `2.5` will become `2`. Since
+    /// amounts are never negative, this rounds midpoints down.
+    HalfTowardZero,
+}
+
+impl Default for RoundingMode {
+    fn default() -> RoundingMode {
+        RoundingMode::HalfUp
+    }
+}
+
+/// English words of the numbers below twenty.
+const SMALL_NUMBER_WORDS: [&'static str; 20] = ["zero", "one", "two", "three", "four", "five",
+                                                "six", "seven", "eight", "nine", "ten", "eleven",
+                                                "twelve", "thirteen", "fourteen", "fifteen",
+                                                "sixteen", "seventeen", "eighteen", "nineteen"];
+
+/// English words of the tens, starting with twenty.
+const TENS_WORDS: [&'static str; 10] = ["", "", "twenty", "thirty", "forty", "fifty", "sixty",
+                                        "seventy", "eighty", "ninety"];
+
+/// English words of the powers of a thousand.
+const SCALE_WORDS: [&'static str; 6] = ["", "thousand", "million", "billion", "trillion",
+                                        "quadrillion"];
+
+/// Writes a number between 1 and 999 in English words.
+fn hundreds_to_words(n: u64) -> String {
+    let mut words = Vec::new();
+    if n >= 100 {
+        words.push(format!("{} hundred", SMALL_NUMBER_WORDS[(n / 100) as usize]));
+    }
+    let rest = (n % 100) as usize;
+    if rest >= 20 {
+        if rest % 10 == 0 {
+            words.push(TENS_WORDS[rest / 10].to_owned());
+        } else {
+            words.push(format!("{}-{}", TENS_WORDS[rest / 10], SMALL_NUMBER_WORDS[rest % 10]));
+        }
+    } else if rest > 0 {
+        words.push(SMALL_NUMBER_WORDS[rest].to_owned());
+    }
+    words.join(" ")
+}
+
+/// Divides `value` by `divisor` rounding the quotient with the given rounding mode.
+fn round_div(value: u128, divisor: u128, mode: RoundingMode) -> u128 {
+    let quotient = value / divisor;
+    let rem = value % divisor;
+    // Comparing `rem` with `divisor - rem` avoids overflowing when doubling the remainder.
+    let round_up = match mode {
+        RoundingMode::HalfUp | RoundingMode::HalfAwayFromZero => rem >= divisor - rem,
+        RoundingMode::HalfTowardZero => rem > divisor - rem,
+        RoundingMode::HalfEven => {
+            rem > divisor - rem || (rem == divisor - rem && quotient % 2 == 1)
+        }
+    };
+    if round_up { quotient + 1 } else { quotient }
+}
+
+/// Returns the number of decimal digits of the given number.
+fn digit_count(mut n: u64) -> usize {
+    let mut count = 1;
+    while n >= 10 {
+        n /= 10;
+        count += 1;
+    }
+    count
+}
+
+/// Splits the given amount representation in the parts to display with the given precision,
+/// rounding with the given mode if the precision requires it.
+///
+/// It returns the units, the decimals, how many decimal digits to show and how many trailing zeros
+/// to add after them. Rounded values always fit in a `u64`, since they are at most
+/// `u64::MAX / 10` plus one.
+fn amount_parts(value: u64,
+                precision: Option<usize>,
+                mode: RoundingMode)
+                -> (u64, u64, usize, usize) {
+    match precision {
+        None => {
+            let units = value / 1_000;
+            let decimal_repr = value % 1_000;
+            if decimal_repr == 0 {
+                (units, 0, 0, 0)
+            } else if decimal_repr % 100 == 0 {
+                (units, decimal_repr / 100, 1, 0)
+            } else if decimal_repr % 10 == 0 {
+                (units, decimal_repr / 10, 2, 0)
+            } else {
+                (units, decimal_repr, 3, 0)
+            }
+        }
+        Some(0) => (round_div(value as u128, 1_000, mode) as u64, 0, 0, 0),
+        Some(1) => {
+            let rounded = round_div(value as u128, 100, mode) as u64;
+            (rounded / 10, rounded % 10, 1, 0)
+        }
+        Some(2) => {
+            let rounded = round_div(value as u128, 10, mode) as u64;
+            (rounded / 100, rounded % 100, 2, 0)
+        }
+        Some(p) => (value / 1_000, value % 1_000, 3, p - 3),
     }
 }
 
+/// Formats the given amount representation, rounding with the given mode if the formatter
+/// precision requires it.
+///
+/// The amount is written directly to the formatter, without intermediate strings. Its length is
+/// computed beforehand, so that it can be padded with zeros up to the formatter width.
+fn fmt_amount(value: u64, mode: RoundingMode, f: &mut fmt::Formatter) -> fmt::Result {
+    let (units, decimals, decimal_digits, trailing_zeros) = amount_parts(value,
+                                                                         f.precision(),
+                                                                         mode);
+    let mut len = digit_count(units);
+    if decimal_digits > 0 {
+        len += 1 + decimal_digits + trailing_zeros;
+    }
+    if let Some(w) = f.width() {
+        if w > len {
+            write!(f, "{:0<1$}", "", w - len)?;
+        }
+    }
+
+    write!(f, "{}", units)?;
+    if decimal_digits > 0 {
+        write!(f, ".{:02$}{:0<3$}", decimals, "", decimal_digits, trailing_zeros)?;
+    }
+    Ok(())
+}
+
 impl fmt::Display for Amount {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let units = self.value / 1_000;
-        let decimal_repr = self.value % 1_000;
-        let result = match f.precision() {
-            None => {
-                if decimal_repr == 0 {
-                    format!("{}", units)
-                } else if decimal_repr % 100 == 0 {
-                    format!("{}.{:01}", units, decimal_repr / 100)
-                } else if decimal_repr % 10 == 0 {
-                    format!("{}.{:02}", units, decimal_repr / 10)
-                } else {
-                    format!("{}.{:03}", units, decimal_repr)
-                }
-            }
-            Some(0) => {
-                format!("{}",
-                        if decimal_repr >= 500 {
-                            units + 1
-                        } else {
-                            units
-                        })
-            }
-            Some(1) => {
-                format!("{}.{:01}",
-                        units,
-                        if decimal_repr % 100 >= 50 {
-                            decimal_repr / 100 + 1
-                        } else {
-                            decimal_repr / 100
-                        })
-            }
-            Some(2) => {
-                format!("{}.{:02}",
-                        units,
-                        if decimal_repr % 10 >= 5 {
-                            decimal_repr / 10 + 1
-                        } else {
-                            decimal_repr / 10
-                        })
-            }
-            Some(p) => {
-                let mut string = format!("{}.{:03}", units, decimal_repr);
-                for _ in 3..p {
-                    string.push('0');
-                }
-                string
+        fmt_amount(self.value, RoundingMode::default(), f)
+    }
+}
+
+/// Amount displayed with a specific rounding mode.
+///
+/// This struct is created with `Amount::display_rounded()`, and it formats the amount the same
+/// way as the `Amount` `Display` implementation, but rounding with the selected `RoundingMode`
+/// when the precision of the formatter is smaller than the precision of the amount.
+#[derive(Copy, Clone, Debug)]
+pub struct RoundedAmount {
+    amount: Amount,
+    mode: RoundingMode,
+}
+
+impl fmt::Display for RoundedAmount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_amount(self.amount.value, self.mode, f)
+    }
+}
+
+/// Amount displayed with thousands separators.
+///
+/// This struct is created with `Amount::display_grouped()`. Units are grouped in thousands with
+/// commas, and decimals are shown as in the `Amount` `Display` implementation, rounding half up
+/// when the precision of the formatter requires it. Unlike `Amount`, it supports the whole
+/// formatting syntax of integers: the `+` flag, fill and alignment for widths, and the `0` flag to
+/// pad with zeros after the sign.
+#[derive(Copy, Clone, Debug)]
+pub struct GroupedAmount {
+    amount: Amount,
+}
+
+impl fmt::Display for GroupedAmount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (units, decimals, decimal_digits, trailing_zeros) =
+            amount_parts(self.amount.value, f.precision(), RoundingMode::default());
+        let digits = units.to_string();
+        let mut grouped = String::with_capacity(digits.len() * 4 / 3 + decimal_digits +
+                                                trailing_zeros + 1);
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i) % 3 == 0 {
+                grouped.push(',');
             }
-        };
+            grouped.push(digit);
+        }
+        if decimal_digits > 0 {
+            let _ = write!(grouped,
+                           ".{:02$}{:0<3$}",
+                           decimals,
+                           "",
+                           decimal_digits,
+                           trailing_zeros);
+        }
+        f.pad_integral(true, "", &grouped)
+    }
+}
 
-        match f.width() {
-            None => write!(f, "{}", result),
-            Some(w) => {
-                if w < result.len() {
-                    write!(f, "{}", result)
-                } else {
-                    let mut pad = String::new();
-                    for _ in result.len()..w {
-                        pad.push('0');
-                    }
-                    write!(f, "{}{}", pad, result)
-                }
+/// List of amounts displayed along with the currency symbol.
+///
+/// Each amount is displayed after the `CURRENCY_SYMBOL`, as with `Amount::format_currency()`, and
+/// they are separated with commas. The precision of the formatter is used for each amount:
+///
+/// ```
+/// use fractal_utils::amount::{Amount, AmountList};
+///
+/// let amounts = [Amount::from_repr(175_646), Amount::from_repr(56_000)];
+/// assert_eq!(AmountList(&amounts).to_string(), "₣ 175.646, ₣ 56");
+/// assert_eq!(format!("{:.1}", AmountList(&amounts)), "₣ 175.6, ₣ 56.0");
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct AmountList<'a>(pub &'a [Amount]);
+
+impl<'a> fmt::Display for AmountList<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, amount) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            match f.precision() {
+                Some(p) => write!(f, "{} {:.*}", CURRENCY_SYMBOL, p, amount)?,
+                None => write!(f, "{} {}", CURRENCY_SYMBOL, amount)?,
             }
         }
+        Ok(())
     }
 }
 
+/// Result of an operation that might lose precision.
+///
+/// This struct is returned by `Amount::div_tracked()`, and it holds the resulting amount along
+/// with a flag telling if the operation lost precision, so that audit trails can record it.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct LossyAmount {
+    /// The resulting amount, truncated if precision was lost
+    pub amount: Amount,
+    /// Whether the operation lost precision
+    pub lossy: bool,
+}
+
+/// Returns test vectors of amount strings, their expected representations and their canonical
+/// `Display` strings.
+///
+/// These are meant for implementations of amounts in other languages, which can check that they
+/// parse and format amounts the same way. They include rounding boundaries, the maximum amount
+/// and inputs without units, such as `.6465`.
+///
+/// ```
+/// use fractal_utils::Amount;
+/// use fractal_utils::amount::test_vectors;
+///
+/// for (input, repr, display) in test_vectors() {
+///     let amount = input.parse::<Amount>().unwrap();
+///     assert_eq!(amount.get_repr(), repr);
+///     assert_eq!(amount.to_string(), display);
+/// }
+/// ```
+#[cfg(feature = "test-vectors")]
+pub fn test_vectors() -> Vec<(&'static str, u64, &'static str)> {
+    vec![("0", 0, "0"),
+         ("175", 175_000, "175"),
+         ("175.646", 175_646, "175.646"),
+         ("175.640", 175_640, "175.64"),
+         ("175.000", 175_000, "175"),
+         ("+1.25", 1_250, "1.25"),
+         (".6465", 647, "0.647"),
+         ("175.6464", 175_646, "175.646"),
+         ("175.6465", 175_647, "175.647"),
+         ("0.0004", 0, "0"),
+         ("0.0005", 1, "0.001"),
+         ("17.9995", 18_000, "18"),
+         ("175.6464999999999999", 175_646, "175.646"),
+         ("18446744073709551.615", u64::MAX, "18446744073709551.615"),
+         ("18446744073709551.6154", u64::MAX, "18446744073709551.615")]
+}
+
 /// Amount parsing error.
 ///
 /// This struct represents an amount parsing error. It explains the exact error that lead to the
-/// parsing error, and implements common `Error` and `Display` traits.
+/// parsing error, and implements common `Error` and `Display` traits. When the error was caused by
+/// a specific character, its byte offset in the parsed string is also available:
+///
+/// ```
+/// use fractal_utils::Amount;
+///
+/// let error = "175.837.9239".parse::<Amount>().unwrap_err();
+/// assert_eq!(error.position(), Some(7));
+/// ```
 #[derive(Debug)]
 pub struct AmountParseError {
     description: String,
+    position: Option<usize>,
     cause: Option<ParseIntError>,
 }
 
@@ -217,9 +1676,62 @@ impl AmountParseError {
             description: format!("the amount {:?} is not a valid Fractal Global amount, {}",
                                  amount.as_ref(),
                                  error.as_ref()),
+            position: None,
             cause: cause,
         }
     }
+
+    /// Creates the error for amounts bigger than the maximum amount.
+    fn too_big(amount: &str) -> AmountParseError {
+        AmountParseError::new(amount,
+                              &format!("it is too big, the maximum amount is {}",
+                                       Amount::max_value()),
+                              None)
+    }
+
+    /// Sets the byte offset of the character that caused the error, if any.
+    fn at(mut self, position: Option<usize>) -> AmountParseError {
+        if let Some(p) = position {
+            self.description = format!("{} (at byte {})", self.description, p);
+        }
+        self.position = position;
+        self
+    }
+
+    /// Returns the byte offset in the parsed string of the character that caused the error.
+    ///
+    /// This is only available for errors caused by a specific character, such as an invalid digit
+    /// or a second decimal separator.
+    pub fn position(&self) -> Option<usize> {
+        self.position
+    }
+}
+
+/// Returns the byte offset of the first character in the string that is not a decimal digit.
+fn first_non_digit(s: &str) -> Option<usize> {
+    s.find(|c: char| !c.is_digit(10))
+}
+
+/// Returns the byte offset of the first misplaced thousands separator in the given units, if any.
+///
+/// Separators must split the units in groups of three digits, except for the first group, which
+/// can have one to three digits.
+fn misplaced_separator(units: &str) -> Option<usize> {
+    let mut group_start = 0;
+    for (i, byte) in units.bytes().enumerate() {
+        if byte == b',' {
+            let group_len = i - group_start;
+            if group_len == 0 || group_len > 3 || (group_start > 0 && group_len != 3) {
+                return Some(i);
+            }
+            group_start = i + 1;
+        }
+    }
+    if group_start > 0 && units.len() - group_start != 3 {
+        Some(group_start - 1)
+    } else {
+        None
+    }
 }
 
 impl fmt::Display for AmountParseError {
@@ -241,100 +1753,275 @@ impl Error for AmountParseError {
     }
 }
 
+/// Statement reconciliation error.
+///
+/// This enum represents the errors that can happen when reconciling credits and debits with
+/// `Amount::reconcile()`, and implements common `Error` and `Display` traits.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ReconcileError {
+    /// The sum of the credits or the sum of the debits is bigger than the maximum amount.
+    Overflow,
+    /// The debits exceed the credits.
+    InsufficientCredits {
+        /// The sum of the credits.
+        credits: Amount,
+        /// The sum of the debits.
+        debits: Amount,
+    },
+}
+
+impl fmt::Display for ReconcileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ReconcileError::Overflow => {
+                write!(f,
+                       "the sum of the amounts is too big, the maximum amount is {}",
+                       Amount::max_value())
+            }
+            ReconcileError::InsufficientCredits { credits, debits } => {
+                write!(f,
+                       "the debits ({} {}) exceed the credits ({} {})",
+                       CURRENCY_SYMBOL,
+                       debits,
+                       CURRENCY_SYMBOL,
+                       credits)
+            }
+        }
+    }
+}
+
+impl Error for ReconcileError {
+    fn description(&self) -> &str {
+        match *self {
+            ReconcileError::Overflow => "the sum of the amounts is too big",
+            ReconcileError::InsufficientCredits { .. } => "the debits exceed the credits",
+        }
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        None
+    }
+}
+
+/// Insufficient payment error.
+///
+/// This struct represents the error returned by `Amount::change()` when the paid amount is smaller
+/// than the due amount, and implements common `Error` and `Display` traits.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct InsufficientFunds {
+    /// The due amount.
+    pub due: Amount,
+    /// The paid amount.
+    pub paid: Amount,
+}
+
+impl fmt::Display for InsufficientFunds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,
+               "the paid amount ({} {}) is smaller than the due amount ({} {})",
+               CURRENCY_SYMBOL,
+               self.paid,
+               CURRENCY_SYMBOL,
+               self.due)
+    }
+}
+
+impl Error for InsufficientFunds {
+    fn description(&self) -> &str {
+        "the paid amount is smaller than the due amount"
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        None
+    }
+}
+
+/// Parses an amount as `FromStr` does. If `strict` is set, superfluous leading zeros in the units
+/// part are rejected, once the currency symbol and the sign have been skipped.
+fn parse_amount(s: &str, strict: bool) -> Result<Amount, AmountParseError> {
+    // A leading currency symbol is accepted, as written by `format_currency()`, along with
+    // whitespace around the number. The offset of the number is kept for error positions.
+    let trimmed = s.trim_start();
+    let (offset, number) = if trimmed.starts_with(CURRENCY_SYMBOL) {
+        let rest = trimmed[CURRENCY_SYMBOL.len_utf8()..].trim_start();
+        (s.len() - rest.len(), rest.trim_end())
+    } else {
+        (0, s)
+    };
+
+    // The amount is parsed in a single pass over each part, without allocating. Only the
+    // first three decimals are needed, and the fourth one to round half up.
+    let (units_str, decimals_str) = match number.find('.') {
+        Some(p) => (&number[..p], Some(&number[p + 1..])),
+        None => (number, None),
+    };
+
+    if let Some(d) = decimals_str {
+        if let Some(p) = d.find('.') {
+            return Err(AmountParseError::new(s,
+                                             "an amount can only have one period to \
+                                              separate units and decimals",
+                                             None)
+                .at(Some(offset + units_str.len() + 1 + p)));
+        }
+    }
+
+    // An explicit positive sign is accepted, as with integers.
+    let digits = if units_str.starts_with('+') {
+        &units_str[1..]
+    } else {
+        units_str
+    };
+    if strict && digits.len() > 1 && digits.starts_with('0') {
+        return Err(AmountParseError::new(s, "the units part has leading zeros", None)
+            .at(Some(offset + units_str.len() - digits.len())));
+    }
+    // The units can only be empty if there are decimals, as in `.5`. They can have thousands
+    // separators, which are checked after the digits.
+    let first_invalid = |s: &str| s.find(|c: char| !c.is_digit(10) && c != ',');
+    let missing_units = digits.is_empty() && (decimals_str.is_none() || digits != units_str);
+    if missing_units || first_invalid(digits).is_some() {
+        let error = match decimals_str {
+            Some(_) => {
+                AmountParseError::new(s,
+                                      "the units part it is not a valid u64 amount",
+                                      units_str.parse::<u64>().err())
+            }
+            None => AmountParseError::new(s, "it is not a valid u64 number", None),
+        };
+        return Err(error.at(first_invalid(units_str).map(|p| offset + p)));
+    }
+    if let Some(p) = misplaced_separator(digits) {
+        return Err(AmountParseError::new(s,
+                                         "the thousands separators of the units must \
+                                          separate groups of three digits",
+                                         None)
+            .at(Some(offset + units_str.len() - digits.len() + p)));
+    }
+
+    // Units that don't even fit in an `u64` are only reported as too big for whole amounts.
+    // With decimals, they are reported as invalid units, as they have always been.
+    let mut units = 0u64;
+    for digit in digits.bytes().filter(|&b| b != b',') {
+        units = match units.checked_mul(10).and_then(|u| u.checked_add((digit - b'0') as u64)) {
+            Some(u) => u,
+            None if decimals_str.is_some() => {
+                return Err(AmountParseError::new(s,
+                                                 "the units part it is not a valid u64 \
+                                                  amount",
+                                                 digits.replace(',', "").parse::<u64>().err())
+                    .at(first_non_digit(units_str).map(|p| offset + p)));
+            }
+            None => return Err(AmountParseError::too_big(s)),
+        };
+    }
+    if units > u64::MAX / 1_000 {
+        return Err(AmountParseError::too_big(s));
+    }
+
+    let mut decimals = 0u64;
+    if let Some(decimals_str) = decimals_str {
+        if decimals_str.is_empty() {
+            return Err(AmountParseError::new(s,
+                                             "no decimals were found after the decimal \
+                                              separator",
+                                             None));
+        }
+        // The decimal part has always been read as an `u64`, so a leading sign is accepted,
+        // and it takes the place of a leading zero: `5.+5` is `5.050`. Decimals that don't
+        // fit in an `u64` are rejected.
+        let sign_len = if decimals_str.starts_with('+') { 1 } else { 0 };
+        let decimal_digits = &decimals_str[sign_len..];
+        if first_non_digit(decimal_digits).is_some() {
+            return Err(AmountParseError::new(s,
+                                             "the decimal part is not a valid u64 number",
+                                             None)
+                .at(first_non_digit(decimals_str).map(|p| offset + units_str.len() + 1 + p)));
+        }
+        if decimal_digits.len() > 19 && decimal_digits.parse::<u64>().is_err() {
+            return Err(AmountParseError::new(s,
+                                             "the decimal part is not a valid u64 number",
+                                             None));
+        }
+
+        let mut padded = iter::repeat(b'0').take(sign_len).chain(decimal_digits.bytes());
+        let mut factor = 100;
+        for digit in padded.by_ref().take(3) {
+            decimals += (digit - b'0') as u64 * factor;
+            factor /= 10;
+        }
+        if padded.next().map_or(false, |d| d >= b'5') {
+            decimals += 1;
+        }
+    }
+
+    // The units are at most `u64::MAX / 1_000 * 1_000`, so only the decimal part of the
+    // maximum amount can be added to them. Rounding might carry the decimals up to `1_000`,
+    // so the check is done without assuming otherwise.
+    let repr = units as u128 * 1_000 + decimals as u128;
+    if repr <= u64::MAX as u128 {
+        Ok(Amount::from_repr(repr as u64))
+    } else {
+        Err(AmountParseError::too_big(s))
+    }
+}
+
+/// Amounts are parsed from their decimal representation, with up to three decimals, or more if
+/// they can be rounded half up. The units can have comma thousands separators, as long as they
+/// separate groups of three digits:
+///
+/// ```
+/// use fractal_utils::Amount;
+///
+/// assert_eq!("1,234.567".parse::<Amount>().unwrap(), Amount::from_repr(1_234_567));
+/// assert!("1,23.4".parse::<Amount>().is_err());
+/// ```
+///
+/// A leading `CURRENCY_SYMBOL` is accepted too, with or without whitespace around the number, so
+/// that the strings of `Amount::format_currency()` with the symbol before the amount can be parsed:
+///
+/// ```
+/// use fractal_utils::{Amount, CURRENCY_SYMBOL};
+///
+/// let amount = Amount::from_repr(175_646);
+/// assert_eq!(format!("{} {}", CURRENCY_SYMBOL, amount).parse::<Amount>().unwrap(), amount);
+/// assert_eq!("₣175.646".parse::<Amount>().unwrap(), amount);
+/// ```
 impl FromStr for Amount {
     type Err = AmountParseError;
     fn from_str(s: &str) -> Result<Amount, AmountParseError> {
-        if s.contains('.') {
-            let parts = s.split('.').count();
-            let mut split = s.split('.');
-            match parts {
-                2 => {
-                    let units_str = split.next().unwrap();
-                    let units: u64 = if units_str != "" {
-                        match units_str.parse::<u64>() {
-                            Ok(u) => {
-                                if u <= u64::MAX / 1_000 {
-                                    u * 1_000
-                                } else {
-                                    return Err(AmountParseError::new(s,
-                                                &format!("it is too big, the maximum amount is {}",
-                                                Amount::max_value()), None));
-                                }
-                            }
-                            Err(e) => {
-                                return Err(AmountParseError::new(s,
-                                                                 "the units part it is not a \
-                                                                  valid u64 amount",
-                                                                 Some(e)))
-                            }
-                        }
-                    } else {
-                        0
-                    };
-                    let mut decimals_str = String::from(split.next().unwrap());
-                    if decimals_str.is_empty() {
-                        return Err(AmountParseError::new(s,
-                                                         "no decimals were found after the \
-                                                          decimal separator",
-                                                         None));
-                    }
-                    while decimals_str.len() < 3 {
-                        decimals_str.push('0');
-                    }
-                    let decimals: u64 = match decimals_str.parse() {
-                        Ok(d) => {
-                            if decimals_str.len() == 3 {
-                                d
-                            } else {
-                                let divisor = 10u64.pow(decimals_str.len() as u32 - 3);
-                                let rem = d % divisor;
-                                if rem >= divisor / 2 {
-                                    d / divisor + 1
-                                } else {
-                                    d / divisor
-                                }
-                            }
-                        }
-                        Err(_) => {
-                            return Err(AmountParseError::new(s,
-                                                             "the decimal part is not a valid \
-                                                              u64 number",
-                                                             None))
-                        }
-                    };
-
-                    if (u64::MAX - decimals) >= units {
-                        Ok(Amount::from_repr(units + decimals))
-                    } else {
-                        Err(AmountParseError::new(s,
-                                                  &format!("it is too big, the maximum amount \
-                                                            is {}",
-                                                           Amount::max_value()),
-                                                  None))
-                    }
-                }
-                _ => {
-                    Err(AmountParseError::new(s,
-                                              "an amount can only have one period to separate \
-                                               units and decimals",
-                                              None))
-                }
-            }
+        parse_amount(s, false)
+    }
+}
+
+/// Amounts can be created from floats, rounding them to the nearest thousandth. Negative, NaN and
+/// infinite floats, or floats bigger than the maximum amount, are not valid amounts:
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use fractal_utils::Amount;
+///
+/// assert_eq!(Amount::try_from(175.646f64).unwrap(), Amount::from_repr(175_646));
+/// assert!(Amount::try_from(-1f64).is_err());
+/// ```
+impl TryFrom<f64> for Amount {
+    type Error = AmountParseError;
+    fn try_from(value: f64) -> Result<Amount, AmountParseError> {
+        let s = value.to_string();
+        if value.is_nan() {
+            return Err(AmountParseError::new(&s[..], "it is not a number", None));
+        }
+        if value < 0.0 {
+            return Err(AmountParseError::new(&s[..], "it is negative", None));
+        }
+
+        // The maximum amount can't be represented exactly as a float, and rounds up to 2^64, which
+        // is too big. Infinity is too big too.
+        let repr = (value * 1_000.0).round();
+        if repr >= u64::MAX as f64 {
+            Err(AmountParseError::too_big(&s))
         } else {
-            match s.parse::<u64>() {
-                Ok(v) => {
-                    if v <= u64::MAX / 1_000 {
-                        Ok(Amount::from_repr(v * 1_000))
-                    } else {
-                        Err(AmountParseError::new(s,
-                                                  &format!("it is too big, the maximum amount \
-                                                            is {}",
-                                                           Amount::max_value()),
-                                                  None))
-                    }
-                }
-                Err(_) => Err(AmountParseError::new(s, "it is not a valid u64 number", None)),
-            }
+            Ok(Amount::from_repr(repr as u64))
         }
     }
 }
@@ -349,6 +2036,13 @@ impl fmt::Debug for Amount {
     }
 }
 
+/// The default amount is zero.
+impl Default for Amount {
+    fn default() -> Amount {
+        Amount::zero()
+    }
+}
+
 impl Encodable for Amount {
     fn encode<S: Encoder>(&self, s: &mut S) -> Result<(), S::Error> {
         s.emit_u64(self.value)
@@ -364,8 +2058,34 @@ impl Decodable for Amount {
     }
 }
 
+/// Unsigned integer types that amounts can be divided by.
+///
+/// These are the types of the `Div` and `Rem` implementations of `Amount`, and the ones accepted
+/// by `Amount::checked_div_int()` and `Amount::checked_rem_int()`. This trait is sealed: it can't
+/// be implemented outside of this crate.
+pub trait IntDivisor: private::Sealed + Copy {
+    /// Converts the divisor to a `u64`.
+    fn to_u64(self) -> u64;
+}
+
+mod private {
+    /// Prevents implementing `IntDivisor` for other types.
+    pub trait Sealed {}
+}
+
 macro_rules! impl_ops_int {
     ($($t:ty)*) => ($(
+        impl private::Sealed for $t {}
+
+        impl IntDivisor for $t {
+            fn to_u64(self) -> u64 {
+                self as u64
+            }
+        }
+
+        /// Divides the amount by an integer, truncating the decimals that don't fit in the
+        /// precision of the amount. It panics if the divisor is zero, use
+        /// `Amount::checked_div_int()` to avoid it.
         impl Div<$t> for Amount {
             type Output = Amount;
 
@@ -380,6 +2100,9 @@ macro_rules! impl_ops_int {
             }
         }
 
+        /// Computes the remainder of the amount divided by an integer number of credits, unlike
+        /// the `Div` implementation, which divides the amount by a scalar. It panics if the
+        /// divisor is zero, use `Amount::checked_rem_int()` to avoid it.
         impl Rem<$t> for Amount {
             type Output = Amount;
 
@@ -0,0 +1,66 @@
+//! Serialization compatibility between `rustc_serialize` and `serde`
+//!
+//! This module holds functions to convert `Amount`s to and from the JSON values of both
+//! `rustc_serialize` and `serde_json`. Both of them represent the amount with its internal
+//! representation, as the `Encodable` implementation does, so that data serialized with one of them
+//! can be read with the other:
+//!
+//! ```
+//! extern crate fractal_utils;
+//! extern crate serde_json;
+//!
+//! use fractal_utils::Amount;
+//! use fractal_utils::compat;
+//!
+//! # fn main() {
+//! let amount = Amount::from_repr(175_646);
+//! let encoded = compat::amount_to_rustc_json(&amount).to_string();
+//! assert_eq!(encoded, "175646");
+//!
+//! let value: serde_json::Value = serde_json::from_str(&encoded).unwrap();
+//! assert_eq!(compat::amount_from_serde_json(&value), Some(amount));
+//! # }
+//! ```
+
+use rustc_serialize::json::Json;
+use serde_json::Value;
+
+use amount::Amount;
+
+/// Converts an amount to a `rustc_serialize` JSON value with its internal representation.
+pub fn amount_to_rustc_json(amount: &Amount) -> Json {
+    Json::U64(amount.get_repr())
+}
+
+/// Reads an amount from a `rustc_serialize` JSON value with its internal representation.
+///
+/// Returns `None` if the value is not an unsigned integer.
+pub fn amount_from_rustc_json(json: &Json) -> Option<Amount> {
+    json.as_u64().map(Amount::from_repr)
+}
+
+/// Converts an amount to a `serde_json` value with its internal representation.
+pub fn amount_to_serde_json(amount: &Amount) -> Value {
+    Value::from(amount.get_repr())
+}
+
+/// Reads an amount from a `serde_json` value with its internal representation.
+///
+/// Returns `None` if the value is not an unsigned integer.
+pub fn amount_from_serde_json(value: &Value) -> Option<Amount> {
+    value.as_u64().map(Amount::from_repr)
+}
+
+/// Converts an amount encoded as a `rustc_serialize` JSON value to a `serde_json` value.
+///
+/// Returns `None` if the value is not a valid amount.
+pub fn rustc_json_to_serde_json(json: &Json) -> Option<Value> {
+    amount_from_rustc_json(json).map(|amount| amount_to_serde_json(&amount))
+}
+
+/// Converts an amount encoded as a `serde_json` value to a `rustc_serialize` JSON value.
+///
+/// Returns `None` if the value is not a valid amount.
+pub fn serde_json_to_rustc_json(value: &Value) -> Option<Json> {
+    amount_from_serde_json(value).map(|amount| amount_to_rustc_json(&amount))
+}
@@ -0,0 +1,109 @@
+//! Fractal Global utilities error
+//!
+//! This module holds the `FractalError` type, that unifies the errors of the rest of the modules,
+//! so that functions working with several of them can use the `?` operator.
+
+use std::convert::From;
+use std::error::Error;
+use std::fmt;
+
+use amount::{AmountParseError, ReconcileError, InsufficientFunds};
+use wallet_address::WalletAddressParseError;
+use location::AddressError;
+
+/// Crate level error.
+///
+/// Each module error can be converted into a `FractalError`, which allows functions returning
+/// `Result<_, FractalError>` to use the `?` operator with any of them:
+///
+/// ```
+/// use fractal_utils::{Amount, FractalError, WalletAddress};
+///
+/// fn parse_transfer(amount: &str, address: &str)
+///                   -> Result<(Amount, WalletAddress), FractalError> {
+///     Ok((amount.parse()?, address.parse()?))
+/// }
+///
+/// assert!(parse_transfer("175.646", "fr111111111").is_ok());
+/// assert!(parse_transfer("175.646", "fr").is_err());
+/// ```
+///
+/// The original error is kept, and it can be retrieved by matching the variants or by using the
+/// `cause()` method.
+#[derive(Debug)]
+pub enum FractalError {
+    /// Error parsing an amount.
+    AmountParse(AmountParseError),
+    /// Error reconciling credits and debits.
+    Reconcile(ReconcileError),
+    /// Error computing the change of a payment.
+    InsufficientFunds(InsufficientFunds),
+    /// Error parsing a wallet address.
+    WalletAddressParse(WalletAddressParseError),
+    /// Error validating a location address.
+    Address(AddressError),
+}
+
+impl From<AmountParseError> for FractalError {
+    fn from(error: AmountParseError) -> FractalError {
+        FractalError::AmountParse(error)
+    }
+}
+
+impl From<ReconcileError> for FractalError {
+    fn from(error: ReconcileError) -> FractalError {
+        FractalError::Reconcile(error)
+    }
+}
+
+impl From<InsufficientFunds> for FractalError {
+    fn from(error: InsufficientFunds) -> FractalError {
+        FractalError::InsufficientFunds(error)
+    }
+}
+
+impl From<WalletAddressParseError> for FractalError {
+    fn from(error: WalletAddressParseError) -> FractalError {
+        FractalError::WalletAddressParse(error)
+    }
+}
+
+impl From<AddressError> for FractalError {
+    fn from(error: AddressError) -> FractalError {
+        FractalError::Address(error)
+    }
+}
+
+impl fmt::Display for FractalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FractalError::AmountParse(ref e) => write!(f, "{}", e),
+            FractalError::Reconcile(ref e) => write!(f, "{}", e),
+            FractalError::InsufficientFunds(ref e) => write!(f, "{}", e),
+            FractalError::WalletAddressParse(ref e) => write!(f, "{}", e),
+            FractalError::Address(ref e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for FractalError {
+    fn description(&self) -> &str {
+        match *self {
+            FractalError::AmountParse(ref e) => e.description(),
+            FractalError::Reconcile(ref e) => e.description(),
+            FractalError::InsufficientFunds(ref e) => e.description(),
+            FractalError::WalletAddressParse(ref e) => e.description(),
+            FractalError::Address(ref e) => e.description(),
+        }
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        match *self {
+            FractalError::AmountParse(ref e) => Some(e),
+            FractalError::Reconcile(ref e) => Some(e),
+            FractalError::InsufficientFunds(ref e) => Some(e),
+            FractalError::WalletAddressParse(ref e) => Some(e),
+            FractalError::Address(ref e) => Some(e),
+        }
+    }
+}
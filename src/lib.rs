@@ -20,14 +20,25 @@
 
 extern crate rustc_serialize;
 extern crate rust_base58;
+#[cfg(feature = "schemars")]
+extern crate schemars;
+#[cfg(feature = "serde_json")]
+extern crate serde_json;
+
+#[macro_use]
+mod macros;
 
 pub mod amount;
 pub mod wallet_address;
 pub mod location;
+pub mod error;
+#[cfg(feature = "serde_json")]
+pub mod compat;
 
-pub use amount::Amount;
+pub use amount::{Amount, RoundingMode};
 pub use wallet_address::{WALLET_ADDRESS_LEN, WalletAddress};
 pub use location::Address;
+pub use error::FractalError;
 
 /// The symbol of Fractal Global Credits
 ///
@@ -2,11 +2,25 @@
 //!
 //! This module holds the Fractal Global Address, and Geological location data type objects.
 
+use std::fmt;
+use std::error::Error;
+use std::str::FromStr;
+
 #[cfg(feature = "json-types")]
 use rustc_serialize::json;
+#[cfg(feature = "schemars")]
+use schemars::JsonSchema;
+#[cfg(feature = "schemars")]
+use schemars::gen::SchemaGenerator;
+#[cfg(feature = "schemars")]
+use schemars::schema::{Schema, SchemaObject, InstanceType, ObjectValidation};
 
 /// The particulars of the place where an organization or person resides
-#[derive(PartialEq, Debug, Clone, RustcEncodable, RustcDecodable)]
+///
+/// Addresses implement `Eq` and `Hash`, so they can be used in sets and as map keys, for example
+/// to deduplicate contacts. Fields are compared exactly, so addresses that only differ in case
+/// or whitespace are different addresses.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, RustcEncodable, RustcDecodable)]
 pub struct Address {
     /// First Address
     address1: String,
@@ -44,6 +58,48 @@ impl Address {
         }
     }
 
+    /// Parses an `Address` from a single line, with comma separated fields
+    ///
+    /// The fields must be in the following order: first address, second address, city, state,
+    /// zip code and country. The second address is optional, and can either be empty or omitted,
+    /// so the line must have five or six fields. Whitespace around each field is trimmed.
+    ///
+    /// ```
+    /// use fractal_utils::location::Address;
+    ///
+    /// let address = Address::parse_single_line("123 Main St, Springfield, IL, 62704, US")
+    ///     .unwrap();
+    /// assert_eq!(address.get_address2(), None);
+    /// assert_eq!(address.get_city(), "Springfield");
+    ///
+    /// let address = Address::parse_single_line("123 Main St, Apt 4, Springfield, IL, 62704, US")
+    ///     .unwrap();
+    /// assert_eq!(address.get_address2(), Some("Apt 4"));
+    /// ```
+    pub fn parse_single_line(s: &str) -> Result<Address, AddressError> {
+        let fields: Vec<&str> = s.split(',').map(|f| f.trim()).collect();
+        match fields.len() {
+            5 => Ok(Address::new(fields[0], None, fields[1], fields[2], fields[3], fields[4])),
+            6 => {
+                let address2 = if fields[1].is_empty() {
+                    None
+                } else {
+                    Some(fields[1])
+                };
+                Ok(Address::new(fields[0], address2, fields[2], fields[3], fields[4], fields[5]))
+            }
+            found => Err(AddressError::InvalidFieldCount { found: found }),
+        }
+    }
+
+    /// Parses an `Address` from a string
+    ///
+    /// This is the same as `parse_single_line()`, for consistency with `Amount::parse()` and
+    /// `WalletAddress::parse()`.
+    pub fn parse(s: &str) -> Result<Address, AddressError> {
+        Address::parse_single_line(s)
+    }
+
     /// Returns address line 1
     pub fn get_address1(&self) -> &str {
         &self.address1
@@ -76,6 +132,497 @@ impl Address {
     pub fn get_country(&self) -> &str {
         &self.country
     }
+
+    /// Returns a copy of the address with the personal details masked
+    ///
+    /// The street lines and the zip code are replaced by `***`, while the city, state and
+    /// country are kept, so that logs can include a coarse location:
+    ///
+    /// ```
+    /// use fractal_utils::location::Address;
+    ///
+    /// let address = Address::new("Gran Vía 1", None, "Madrid", "Madrid", "28013", "ES");
+    /// let redacted = address.redacted();
+    /// assert_eq!(redacted.get_address1(), "***");
+    /// assert_eq!(redacted.get_zip(), "***");
+    /// assert_eq!(redacted.get_city(), "Madrid");
+    /// ```
+    pub fn redacted(&self) -> Address {
+        const MASK: &'static str = "***";
+        Address {
+            address1: MASK.to_owned(),
+            address2: self.address2.as_ref().map(|_| MASK.to_owned()),
+            city: self.city.clone(),
+            state: self.state.clone(),
+            zip: MASK.to_owned(),
+            country: self.country.clone(),
+        }
+    }
+
+    /// Parses the state of the address, if it's in the United States
+    ///
+    /// It returns `None` if the country is not `US`, in any case. The state field is kept as
+    /// is, so that addresses with invalid states can still be stored:
+    ///
+    /// ```
+    /// use fractal_utils::location::{Address, UsState};
+    ///
+    /// let address = Address::new("123 Main St", None, "Springfield", "Illinois", "62704", "US");
+    /// assert_eq!(address.parse_us_state(), Some(Ok(UsState::Illinois)));
+    /// assert_eq!(address.get_state(), "Illinois");
+    /// ```
+    pub fn parse_us_state(&self) -> Option<Result<UsState, AddressError>> {
+        if self.country.trim().eq_ignore_ascii_case("US") {
+            Some(self.state.parse())
+        } else {
+            None
+        }
+    }
+
+    /// Validates the lengths of the address fields against the default limits
+    ///
+    /// The default limits are the ones in `AddressLengthLimits::default()`. It returns the first
+    /// field that is longer than allowed, if any.
+    pub fn validate_lengths(&self) -> Result<(), AddressError> {
+        self.validate_lengths_with(&AddressLengthLimits::default())
+    }
+
+    /// Validates the lengths of the address fields against the given limits
+    ///
+    /// Lengths are counted in characters, not bytes. It returns the first field that is longer
+    /// than allowed, if any:
+    ///
+    /// ```
+    /// use fractal_utils::location::{Address, AddressError, AddressField, AddressLengthLimits};
+    ///
+    /// let address = Address::new("Gran Vía 1", None, "Madrid", "Madrid", "28013", "ES");
+    /// let limits = AddressLengthLimits { zip: 4, ..Default::default() };
+    /// assert_eq!(address.validate_lengths_with(&limits),
+    ///            Err(AddressError::FieldTooLong {
+    ///                field: AddressField::Zip,
+    ///                max: 4,
+    ///                len: 5,
+    ///            }));
+    /// ```
+    pub fn validate_lengths_with(&self, limits: &AddressLengthLimits) -> Result<(), AddressError> {
+        let mut fields = vec![(AddressField::Address1, &self.address1[..], limits.address1)];
+        if let Some(ref address2) = self.address2 {
+            fields.push((AddressField::Address2, address2, limits.address2));
+        }
+        fields.push((AddressField::City, &self.city, limits.city));
+        fields.push((AddressField::State, &self.state, limits.state));
+        fields.push((AddressField::Zip, &self.zip, limits.zip));
+        fields.push((AddressField::Country, &self.country, limits.country));
+
+        for (field, value, max) in fields {
+            let len = value.chars().count();
+            if len > max {
+                return Err(AddressError::FieldTooLong {
+                    field: field,
+                    max: max,
+                    len: len,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates that the fields required in the country of the address are not empty
+    ///
+    /// The first address, the city and the country are always required. The state is only
+    /// required in countries that use states or provinces in their addresses: `US`, `CA`, `AU`,
+    /// `BR`, `MX` and `IN`. Fields with only whitespace are considered empty. It returns the first
+    /// missing field, if any:
+    ///
+    /// ```
+    /// use fractal_utils::location::{Address, AddressError, AddressField};
+    ///
+    /// let address = Address::new("Gran Vía 1", None, "Madrid", "", "28013", "ES");
+    /// assert_eq!(address.validate_required_fields(), Ok(()));
+    ///
+    /// let address = Address::new("123 Main St", None, "Springfield", "", "62704", "US");
+    /// assert_eq!(address.validate_required_fields(),
+    ///            Err(AddressError::MissingField { field: AddressField::State }));
+    /// ```
+    pub fn validate_required_fields(&self) -> Result<(), AddressError> {
+        let state_required = match &self.country.trim().to_uppercase()[..] {
+            "US" | "CA" | "AU" | "BR" | "MX" | "IN" => true,
+            _ => false,
+        };
+        let mut fields = vec![(AddressField::Address1, &self.address1[..]),
+                              (AddressField::City, &self.city)];
+        if state_required {
+            fields.push((AddressField::State, &self.state));
+        }
+        fields.push((AddressField::Country, &self.country));
+
+        for (field, value) in fields {
+            if value.trim().is_empty() {
+                return Err(AddressError::MissingField { field: field });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks if the address matches the given partial query
+    ///
+    /// Only the fields present in the query are checked, and each of them matches if the field
+    /// of the address contains the query substring, in any case. An empty query matches every
+    /// address:
+    ///
+    /// ```
+    /// use fractal_utils::location::{Address, AddressQuery};
+    ///
+    /// let address = Address::new("123 Main St", None, "Springfield", "IL", "62704", "US");
+    /// let query = AddressQuery {
+    ///     city: Some("springfield".to_owned()),
+    ///     zip: Some("627".to_owned()),
+    ///     ..Default::default()
+    /// };
+    /// assert!(address.matches_query(&query));
+    /// ```
+    pub fn matches_query(&self, query: &AddressQuery) -> bool {
+        let matches = |value: Option<&str>, substring: &Option<String>| match *substring {
+            Some(ref substring) => {
+                value.map_or(false,
+                             |v| v.to_lowercase().contains(&substring.to_lowercase()[..]))
+            }
+            None => true,
+        };
+        matches(Some(&self.address1), &query.address1) &&
+        matches(self.get_address2(), &query.address2) &&
+        matches(Some(&self.city), &query.city) &&
+        matches(Some(&self.state), &query.state) &&
+        matches(Some(&self.zip), &query.zip) &&
+        matches(Some(&self.country), &query.country)
+    }
+
+    /// Checks if the address is equal to the given one, ignoring the second line
+    ///
+    /// Second lines, such as suite or apartment numbers, are often optional, so some matching
+    /// flows should not take them into account. The rest of the fields are compared as with
+    /// `PartialEq`:
+    ///
+    /// ```
+    /// use fractal_utils::location::Address;
+    ///
+    /// let address = Address::new("123 Main St", Some("Suite 5"), "Springfield", "IL", "62704",
+    ///                            "US");
+    /// let other = Address::new("123 Main St", None, "Springfield", "IL", "62704", "US");
+    /// assert!(address.matches_ignoring_line2(&other));
+    /// assert!(address != other);
+    /// ```
+    pub fn matches_ignoring_line2(&self, other: &Address) -> bool {
+        self.address1 == other.address1 && self.city == other.city &&
+        self.state == other.state && self.zip == other.zip && self.country == other.country
+    }
+
+    /// Returns the default IANA timezone of the country of the address
+    ///
+    /// The country is expected as an ISO 3166-1 alpha-2 code (`"ES"`, `"JP"`…), in any case.
+    /// Only countries with a single timezone are supported, since the timezone can't be known
+    /// from the country alone otherwise. For multi-timezone or unknown countries `None` is
+    /// returned.
+    ///
+    /// ```
+    /// use fractal_utils::location::Address;
+    ///
+    /// let address = Address::new("1-1 Marunouchi", None, "Chiyoda", "Tokyo", "100-0005", "JP");
+    /// assert_eq!(address.default_timezone(), Some("Asia/Tokyo"));
+    /// ```
+    pub fn default_timezone(&self) -> Option<&str> {
+        let timezone = match &self.country.trim().to_uppercase()[..] {
+            "AT" => "Europe/Vienna",
+            "BE" => "Europe/Brussels",
+            "CH" => "Europe/Zurich",
+            "CN" => "Asia/Shanghai",
+            "CZ" => "Europe/Prague",
+            "DE" => "Europe/Berlin",
+            "DK" => "Europe/Copenhagen",
+            "FI" => "Europe/Helsinki",
+            "GB" => "Europe/London",
+            "GR" => "Europe/Athens",
+            "HK" => "Asia/Hong_Kong",
+            "IE" => "Europe/Dublin",
+            "IL" => "Asia/Jerusalem",
+            "IN" => "Asia/Kolkata",
+            "IT" => "Europe/Rome",
+            "JP" => "Asia/Tokyo",
+            "KR" => "Asia/Seoul",
+            "NL" => "Europe/Amsterdam",
+            "NO" => "Europe/Oslo",
+            "PL" => "Europe/Warsaw",
+            "SE" => "Europe/Stockholm",
+            "SG" => "Asia/Singapore",
+            "ZA" => "Africa/Johannesburg",
+            _ => return None,
+        };
+        Some(timezone)
+    }
+}
+
+/// The fields of an `Address`
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum AddressField {
+    /// First Address
+    Address1,
+    /// Second Address
+    Address2,
+    /// The City
+    City,
+    /// The State
+    State,
+    /// The Zip Code
+    Zip,
+    /// The Country
+    Country,
+}
+
+impl fmt::Display for AddressField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            AddressField::Address1 => "address1",
+            AddressField::Address2 => "address2",
+            AddressField::City => "city",
+            AddressField::State => "state",
+            AddressField::Zip => "zip",
+            AddressField::Country => "country",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Partial address query, to search addresses with `Address::matches_query()`
+///
+/// Each field holds an optional substring to search in the same field of the addresses. Fields
+/// set to `None` are not checked.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct AddressQuery {
+    /// Substring of the first address
+    pub address1: Option<String>,
+    /// Substring of the second address
+    pub address2: Option<String>,
+    /// Substring of the city
+    pub city: Option<String>,
+    /// Substring of the state
+    pub state: Option<String>,
+    /// Substring of the zip code
+    pub zip: Option<String>,
+    /// Substring of the country
+    pub country: Option<String>,
+}
+
+/// Maximum lengths, in characters, of the fields of an `Address`
+///
+/// These are usually the sizes of the database columns that store the addresses. The default
+/// limits are 100 characters for every field but the zip code, which is limited to 20.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct AddressLengthLimits {
+    /// Maximum length of the first address
+    pub address1: usize,
+    /// Maximum length of the second address
+    pub address2: usize,
+    /// Maximum length of the city
+    pub city: usize,
+    /// Maximum length of the state
+    pub state: usize,
+    /// Maximum length of the zip code
+    pub zip: usize,
+    /// Maximum length of the country
+    pub country: usize,
+}
+
+impl Default for AddressLengthLimits {
+    fn default() -> AddressLengthLimits {
+        AddressLengthLimits {
+            address1: 100,
+            address2: 100,
+            city: 100,
+            state: 100,
+            zip: 20,
+            country: 100,
+        }
+    }
+}
+
+/// Defines the `UsState` enum, with the postal code and the name of each state.
+macro_rules! us_states {
+    ($($variant:ident => $code:expr, $name:expr;)*) => {
+        /// A state of the United States, or the District of Columbia
+        ///
+        /// States can be parsed from their two letter postal code or from their name, in any
+        /// case, and they are displayed with their postal code:
+        ///
+        /// ```
+        /// use fractal_utils::location::UsState;
+        ///
+        /// assert_eq!("CA".parse::<UsState>(), Ok(UsState::California));
+        /// assert_eq!("north carolina".parse::<UsState>(), Ok(UsState::NorthCarolina));
+        /// assert_eq!(UsState::NorthCarolina.to_string(), "NC");
+        /// assert_eq!(UsState::NorthCarolina.get_name(), "North Carolina");
+        /// ```
+        #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+        pub enum UsState {
+            $(
+                #[doc = $name]
+                $variant,
+            )*
+        }
+
+        impl UsState {
+            /// Returns the two letter postal code of the state
+            pub fn get_code(&self) -> &'static str {
+                match *self {
+                    $(UsState::$variant => $code,)*
+                }
+            }
+
+            /// Returns the name of the state
+            pub fn get_name(&self) -> &'static str {
+                match *self {
+                    $(UsState::$variant => $name,)*
+                }
+            }
+        }
+
+        impl FromStr for UsState {
+            type Err = AddressError;
+            fn from_str(s: &str) -> Result<UsState, AddressError> {
+                let s = s.trim();
+                $(
+                    if s.eq_ignore_ascii_case($code) || s.eq_ignore_ascii_case($name) {
+                        return Ok(UsState::$variant);
+                    }
+                )*
+                Err(AddressError::InvalidState)
+            }
+        }
+    };
+}
+
+us_states! {
+    Alabama => "AL", "Alabama";
+    Alaska => "AK", "Alaska";
+    Arizona => "AZ", "Arizona";
+    Arkansas => "AR", "Arkansas";
+    California => "CA", "California";
+    Colorado => "CO", "Colorado";
+    Connecticut => "CT", "Connecticut";
+    Delaware => "DE", "Delaware";
+    DistrictOfColumbia => "DC", "District of Columbia";
+    Florida => "FL", "Florida";
+    Georgia => "GA", "Georgia";
+    Hawaii => "HI", "Hawaii";
+    Idaho => "ID", "Idaho";
+    Illinois => "IL", "Illinois";
+    Indiana => "IN", "Indiana";
+    Iowa => "IA", "Iowa";
+    Kansas => "KS", "Kansas";
+    Kentucky => "KY", "Kentucky";
+    Louisiana => "LA", "Louisiana";
+    Maine => "ME", "Maine";
+    Maryland => "MD", "Maryland";
+    Massachusetts => "MA", "Massachusetts";
+    Michigan => "MI", "Michigan";
+    Minnesota => "MN", "Minnesota";
+    Mississippi => "MS", "Mississippi";
+    Missouri => "MO", "Missouri";
+    Montana => "MT", "Montana";
+    Nebraska => "NE", "Nebraska";
+    Nevada => "NV", "Nevada";
+    NewHampshire => "NH", "New Hampshire";
+    NewJersey => "NJ", "New Jersey";
+    NewMexico => "NM", "New Mexico";
+    NewYork => "NY", "New York";
+    NorthCarolina => "NC", "North Carolina";
+    NorthDakota => "ND", "North Dakota";
+    Ohio => "OH", "Ohio";
+    Oklahoma => "OK", "Oklahoma";
+    Oregon => "OR", "Oregon";
+    Pennsylvania => "PA", "Pennsylvania";
+    RhodeIsland => "RI", "Rhode Island";
+    SouthCarolina => "SC", "South Carolina";
+    SouthDakota => "SD", "South Dakota";
+    Tennessee => "TN", "Tennessee";
+    Texas => "TX", "Texas";
+    Utah => "UT", "Utah";
+    Vermont => "VT", "Vermont";
+    Virginia => "VA", "Virginia";
+    Washington => "WA", "Washington";
+    WestVirginia => "WV", "West Virginia";
+    Wisconsin => "WI", "Wisconsin";
+    Wyoming => "WY", "Wyoming";
+}
+
+impl fmt::Display for UsState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.get_code())
+    }
+}
+
+/// Address validation error
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum AddressError {
+    /// A field is longer than its maximum length
+    FieldTooLong {
+        /// The field that is too long
+        field: AddressField,
+        /// The maximum length of the field
+        max: usize,
+        /// The length of the field
+        len: usize,
+    },
+    /// A single line address does not have five or six fields
+    InvalidFieldCount {
+        /// The number of fields found
+        found: usize,
+    },
+    /// The state is not a known state of the country of the address
+    InvalidState,
+    /// A field required in the country of the address is empty
+    MissingField {
+        /// The missing field
+        field: AddressField,
+    },
+}
+
+impl fmt::Display for AddressError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AddressError::FieldTooLong { field, max, len } => {
+                write!(f,
+                       "the {} of the address is too long, it has {} characters and the maximum \
+                        is {}",
+                       field,
+                       len,
+                       max)
+            }
+            AddressError::InvalidFieldCount { found } => {
+                write!(f,
+                       "the address has {} comma separated fields, but it should have 5 or 6",
+                       found)
+            }
+            AddressError::InvalidState => write!(f, "the state of the address is not valid"),
+            AddressError::MissingField { field } => {
+                write!(f, "the {} of the address is required, but it is empty", field)
+            }
+        }
+    }
+}
+
+impl Error for AddressError {
+    fn description(&self) -> &str {
+        match *self {
+            AddressError::FieldTooLong { .. } => "an address field is too long",
+            AddressError::InvalidFieldCount { .. } => "the address has an invalid field count",
+            AddressError::InvalidState => "the state of the address is not valid",
+            AddressError::MissingField { .. } => "a required address field is empty",
+        }
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        None
+    }
 }
 
 #[cfg(feature = "json-types")]
@@ -91,3 +638,27 @@ impl json::ToJson for Address {
         json::Json::Object(object)
     }
 }
+
+#[cfg(feature = "schemars")]
+impl JsonSchema for Address {
+    fn schema_name() -> String {
+        String::from("Address")
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let mut object = ObjectValidation::default();
+        for field in &["address1", "city", "state", "zip", "country"] {
+            let _ = object.properties.insert(String::from(*field), gen.subschema_for::<String>());
+            let _ = object.required.insert(String::from(*field));
+        }
+        let _ = object.properties
+            .insert(String::from("address2"), gen.subschema_for::<Option<String>>());
+
+        let schema = SchemaObject {
+            instance_type: Some(InstanceType::Object.into()),
+            object: Some(Box::new(object)),
+            ..Default::default()
+        };
+        schema.into()
+    }
+}
@@ -0,0 +1,80 @@
+//! Fractal Global utility macros
+//!
+//! This module holds macros to concisely create amounts and wallet addresses from literals, mostly
+//! useful in tests and examples. They are exported from the crate root.
+
+/// Creates an `Amount` from a string literal or from its representation.
+///
+/// The string form is parsed with `FromStr` at runtime, and the representation form is the same
+/// as `Amount::from_repr()`:
+///
+/// ```
+/// #[macro_use]
+/// extern crate fractal_utils;
+///
+/// use fractal_utils::Amount;
+///
+/// # fn main() {
+/// assert_eq!(amount!("175.646"), Amount::from_repr(175_646));
+/// assert_eq!(amount!(repr: 175_646), Amount::from_repr(175_646));
+/// # }
+/// ```
+///
+/// It will panic if the literal is not a valid amount, with the parsing error in the message:
+///
+/// ```should_panic
+/// #[macro_use]
+/// extern crate fractal_utils;
+///
+/// # fn main() {
+/// let _ = amount!("175.6.46");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! amount {
+    (repr: $repr:expr) => {
+        $crate::Amount::from_repr($repr)
+    };
+    ($s:expr) => {
+        match <$crate::Amount as ::std::str::FromStr>::from_str($s) {
+            Ok(amount) => amount,
+            Err(e) => panic!("invalid amount literal {:?}: {}", $s, e),
+        }
+    };
+}
+
+/// Creates a `WalletAddress` from a string literal.
+///
+/// The string is parsed with `FromStr` at runtime, so the checksum of the address is verified:
+///
+/// ```
+/// #[macro_use]
+/// extern crate fractal_utils;
+///
+/// use fractal_utils::{WalletAddress, WALLET_ADDRESS_LEN};
+///
+/// # fn main() {
+/// assert_eq!(wallet!("fr111111111"), WalletAddress::from_data([0u8; WALLET_ADDRESS_LEN]));
+/// # }
+/// ```
+///
+/// It will panic if the literal is not a valid wallet address, for example if its checksum is
+/// wrong, with the parsing error in the message:
+///
+/// ```should_panic
+/// #[macro_use]
+/// extern crate fractal_utils;
+///
+/// # fn main() {
+/// let _ = wallet!("fr111111112");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! wallet {
+    ($s:expr) => {
+        match <$crate::WalletAddress as ::std::str::FromStr>::from_str($s) {
+            Ok(address) => address,
+            Err(e) => panic!("invalid wallet address literal {:?}: {}", $s, e),
+        }
+    };
+}
@@ -8,11 +8,19 @@ use std::result::Result;
 use std::error::Error;
 use std::{fmt, str};
 use std::str::FromStr;
+use std::cmp::Ordering;
+use std::rc::Rc;
+use std::cell::RefCell;
 
-use rust_base58::{ToBase58, FromBase58};
 use rust_base58::base58::FromBase58Error;
 #[cfg(feature = "json-types")]
 use rustc_serialize::json;
+#[cfg(feature = "schemars")]
+use schemars::JsonSchema;
+#[cfg(feature = "schemars")]
+use schemars::gen::SchemaGenerator;
+#[cfg(feature = "schemars")]
+use schemars::schema::{Schema, SchemaObject, InstanceType, Metadata, StringValidation};
 
 /// The wallet address size.
 ///
@@ -71,6 +79,29 @@ pub const WALLET_ADDRESS_LEN: usize = 7;
 ///
 /// assert_eq!(checksum, [0xAD, 0x07]);
 /// ```
+///
+/// Addresses using the newer `ChecksumScheme::Fletcher` checksum are accepted too. When parsing,
+/// the legacy checksum is checked first, and the Fletcher checksum only if the legacy one fails.
+/// Since a mistyped address is accepted if it matches any of the two checksums, this validation
+/// is weaker than the one of the legacy scheme alone: several times as many single character
+/// typos go undetected. Use `from_str_with_scheme()` to accept a single scheme.
+///
+/// The `Ord` implementation compares the `WALLET_ADDRESS_LEN` address bytes lexicographically,
+/// starting with the version byte, so addresses are first sorted by their version and then by the
+/// rest of their bytes. This order is guaranteed to be stable, so it can be relied on for stored
+/// addresses:
+///
+/// ```
+/// use fractal_utils::WalletAddress;
+///
+/// let first = WalletAddress::from([0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+/// let second = WalletAddress::from([0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+/// assert!(first < second);
+/// ```
+///
+/// Note that this does not always match the alphabetical order of the address strings, since
+/// shorter strings sort by their first digits. Use `cmp_display()` to sort addresses as they are
+/// displayed.
 #[derive(Eq, PartialEq, PartialOrd, Ord, Debug, Clone, Copy, RustcEncodable, RustcDecodable)]
 pub struct WalletAddress {
     address: [u8; WALLET_ADDRESS_LEN],
@@ -91,6 +122,324 @@ impl WalletAddress {
         WalletAddress { address: addr }
     }
 
+    /// Creates a new wallet address from its payload as an integer.
+    ///
+    /// The payload is the part of the address after the first `0x00` byte, stored in big-endian
+    /// order. Since it has `WALLET_ADDRESS_LEN - 1` bytes, the payload must be smaller than
+    /// `2^48`. It returns `None` otherwise.
+    ///
+    /// ```
+    /// use fractal_utils::WalletAddress;
+    ///
+    /// let addr = WalletAddress::from_u64(0x0102).unwrap();
+    /// assert_eq!(addr.get_raw(), &[0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02]);
+    /// assert!(WalletAddress::from_u64(1 << 48).is_none());
+    /// ```
+    pub fn from_u64(payload: u64) -> Option<WalletAddress> {
+        if payload >> ((WALLET_ADDRESS_LEN - 1) * 8) != 0 {
+            return None;
+        }
+        let mut address = [0u8; WALLET_ADDRESS_LEN];
+        for (i, byte) in address[1..].iter_mut().rev().enumerate() {
+            *byte = (payload >> (i * 8)) as u8;
+        }
+        Some(WalletAddress::from_data(address))
+    }
+
+    /// Packs the wallet address with its checksum in an integer.
+    ///
+    /// The `WALLET_ADDRESS_LEN + 2` bytes of the address and its `ChecksumScheme::Xor` checksum
+    /// are stored in big-endian order in the lowest bits of the integer: the version byte in
+    /// bits 64 to 71, the rest of the address in bits 16 to 63 and the checksum in bits 0 to 15.
+    /// The highest bits are zero. This is the same number that is base-58 encoded in the string
+    /// representation:
+    ///
+    /// ```
+    /// use fractal_utils::WalletAddress;
+    ///
+    /// let addr = WalletAddress::from_u64(0x0102).unwrap();
+    /// assert_eq!(addr.to_u128(), 0x00_0000_0000_0102_0302);
+    /// assert_eq!(WalletAddress::from_u128(addr.to_u128()).unwrap(), addr);
+    /// ```
+    pub fn to_u128(&self) -> u128 {
+        self.address
+            .iter()
+            .chain(checksum(&self.address, ChecksumScheme::Xor).iter())
+            .fold(0, |value, byte| value << 8 | *byte as u128)
+    }
+
+    /// Creates a wallet address from an integer packed as in `to_u128()`, verifying it.
+    ///
+    /// As when parsing a string, the version byte must be `0x00` and the checksum must be valid,
+    /// with any of the checksum schemes. It returns an error otherwise, or if any of the highest
+    /// bits of the integer is set.
+    pub fn from_u128(value: u128) -> Result<WalletAddress, WalletAddressParseError> {
+        let description = format!("{:#x}", value);
+        if value >> ((WALLET_ADDRESS_LEN + 2) * 8) != 0 {
+            return Err(WalletAddressParseError::new(&description[..],
+                                                    &format!("the integer does not fit in {} \
+                                                              bytes",
+                                                             WALLET_ADDRESS_LEN + 2),
+                                                    None));
+        }
+        let mut bytes = [0u8; WALLET_ADDRESS_LEN + 2];
+        for (i, byte) in bytes.iter_mut().rev().enumerate() {
+            *byte = (value >> (8 * i)) as u8;
+        }
+        WalletAddress::from_checked_bytes(&description, &bytes, &[0x00], ALL_SCHEMES)
+    }
+
+    /// Generates `count` wallet addresses with sequential payloads starting with `start`.
+    ///
+    /// All generated addresses are valid and distinct, which makes this useful for benchmarks and
+    /// load tests, without the overhead of generating random addresses.
+    ///
+    /// ```
+    /// use fractal_utils::WalletAddress;
+    ///
+    /// let addrs = WalletAddress::generate_sequential(10, 3);
+    /// assert_eq!(addrs[2], WalletAddress::from_u64(12).unwrap());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// It will panic if any of the payloads does not fit in a wallet address, as explained in
+    /// `from_u64()`.
+    pub fn generate_sequential(start: u64, count: usize) -> Vec<WalletAddress> {
+        (0..count as u64)
+            .map(|i| {
+                start.checked_add(i)
+                    .and_then(WalletAddress::from_u64)
+                    .expect("the payload is too big for a wallet address")
+            })
+            .collect()
+    }
+
+    /// Returns the version byte of the wallet address.
+    ///
+    /// This is the first byte of the address, which is `0x00` for all addresses created with
+    /// `from_data()` or parsed with `FromStr`.
+    pub fn get_version(&self) -> u8 {
+        self.address[0]
+    }
+
+    /// Returns a stable seed for identicon generators, derived from the address bytes.
+    ///
+    /// The seed is the 32-bit FNV-1a hash of the address, so it's the same for the same address
+    /// in all runs and platforms, unlike the `Hash` implementations of the standard library:
+    ///
+    /// ```
+    /// use fractal_utils::WalletAddress;
+    ///
+    /// let address = WalletAddress::from_data([0x00, 0x11, 0x2A, 0x44, 0xCD, 0xFF, 0xE0]);
+    /// assert_eq!(address.identicon_seed(), 0xB3E0_090C);
+    /// ```
+    pub fn identicon_seed(&self) -> u32 {
+        self.address.iter().fold(0x811C_9DC5, |hash, byte| {
+            (hash ^ *byte as u32).wrapping_mul(0x0100_0193)
+        })
+    }
+
+    /// Returns the name of the network of the wallet address, if its version byte is known.
+    ///
+    /// Currently the only known version is `0x00`, used by the main network:
+    ///
+    /// ```
+    /// use fractal_utils::{WalletAddress, WALLET_ADDRESS_LEN};
+    ///
+    /// let address = WalletAddress::from_data([0u8; WALLET_ADDRESS_LEN]);
+    /// assert_eq!(address.network_name(), Some("mainnet"));
+    /// ```
+    pub fn network_name(&self) -> Option<&'static str> {
+        match self.get_version() {
+            0x00 => Some("mainnet"),
+            _ => None,
+        }
+    }
+
+    /// Parses a wallet address from a string.
+    ///
+    /// This is the same as the `FromStr` implementation, but it does not require importing the
+    /// `FromStr` trait:
+    ///
+    /// ```
+    /// use fractal_utils::{WalletAddress, WALLET_ADDRESS_LEN};
+    ///
+    /// let address = WalletAddress::parse("fr111111111").unwrap();
+    /// assert_eq!(address, WalletAddress::from_data([0u8; WALLET_ADDRESS_LEN]));
+    /// ```
+    pub fn parse(s: &str) -> Result<WalletAddress, WalletAddressParseError> {
+        s.parse()
+    }
+
+    /// Checks if the given string is a valid wallet address in its canonical form.
+    ///
+    /// The canonical form is the one produced by the `Display` implementation. Strings that can
+    /// be parsed but are not canonical, such as addresses with a `ChecksumScheme::Fletcher`
+    /// checksum, return `false`, as do invalid addresses:
+    ///
+    /// ```
+    /// use fractal_utils::wallet_address::{WalletAddress, ChecksumScheme};
+    ///
+    /// let addr = WalletAddress::from_data([0x00, 0x11, 0x2A, 0x44, 0xCD, 0xFF, 0xE0]);
+    /// assert!(WalletAddress::is_canonical_string(&addr.to_string()));
+    ///
+    /// let fletcher = addr.to_string_with_scheme(ChecksumScheme::Fletcher);
+    /// assert!(!WalletAddress::is_canonical_string(&fletcher));
+    /// ```
+    pub fn is_canonical_string(s: &str) -> bool {
+        match s.parse::<WalletAddress>() {
+            Ok(address) => {
+                let mut buf = [0u8; ENCODED_MAX_LEN];
+                encode(&address.address, ChecksumScheme::Xor, &mut buf) == s
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Parses a wallet address, checking that its version byte is one of the allowed ones.
+    ///
+    /// This can be used to make sure that an address belongs to an expected network. Parsing with
+    /// `FromStr` is the same as allowing only the `0x00` version:
+    ///
+    /// ```
+    /// use fractal_utils::WalletAddress;
+    ///
+    /// let address = WalletAddress::from_str_with_version("fr111111111", &[0x00, 0x01]).unwrap();
+    /// assert_eq!(address.get_version(), 0x00);
+    ///
+    /// assert!(WalletAddress::from_str_with_version("fr111111111", &[0x01]).is_err());
+    /// ```
+    pub fn from_str_with_version(s: &str,
+                                 allowed: &[u8])
+                                 -> Result<WalletAddress, WalletAddressParseError> {
+        WalletAddress::from_prefixed(s, allowed, ALL_SCHEMES)
+    }
+
+    /// Parses a wallet address, only accepting the checksum of the given scheme.
+    ///
+    /// `FromStr` accepts both checksum schemes, which lets more mistyped addresses through. This
+    /// can be used once all the addresses of a system use the same scheme. The version byte must
+    /// be `0x00`, as with `FromStr`:
+    ///
+    /// ```
+    /// use fractal_utils::wallet_address::{WalletAddress, ChecksumScheme};
+    ///
+    /// let addr = WalletAddress::from_data([0x00, 0x11, 0x2A, 0x44, 0xCD, 0xFF, 0xE0]);
+    /// let fletcher = addr.to_string_with_scheme(ChecksumScheme::Fletcher);
+    /// let parsed = WalletAddress::from_str_with_scheme(&fletcher, ChecksumScheme::Fletcher);
+    /// assert_eq!(parsed.unwrap(), addr);
+    /// assert!(WalletAddress::from_str_with_scheme(&fletcher, ChecksumScheme::Xor).is_err());
+    /// ```
+    pub fn from_str_with_scheme(s: &str,
+                                scheme: ChecksumScheme)
+                                -> Result<WalletAddress, WalletAddressParseError> {
+        WalletAddress::from_prefixed(s, &[0x00], &[scheme])
+    }
+
+    /// Parses a wallet address with the `fr` prefix, verifying its version byte and its checksum
+    /// with any of the given schemes.
+    fn from_prefixed(s: &str,
+                     allowed: &[u8],
+                     schemes: &[ChecksumScheme])
+                     -> Result<WalletAddress, WalletAddressParseError> {
+        if !s.starts_with("fr") {
+            return Err(WalletAddressParseError::new(s,
+                                                    "the address does not start with \"fr\"",
+                                                    None));
+        }
+        WalletAddress::from_encoded(s, 2, allowed, schemes)
+    }
+
+    /// Parses the base-58 encoded part of a wallet address, without the `fr` prefix.
+    ///
+    /// This allows recovering addresses from strings that lost their prefix, for example in
+    /// storage. The checksum and the `0x00` version byte are verified as with `FromStr`:
+    ///
+    /// ```
+    /// use fractal_utils::{WalletAddress, WALLET_ADDRESS_LEN};
+    ///
+    /// let address = WalletAddress::from_body_str("111111111").unwrap();
+    /// assert_eq!(address, WalletAddress::from_data([0u8; WALLET_ADDRESS_LEN]));
+    ///
+    /// assert!(WalletAddress::from_body_str("111111112").is_err());
+    /// ```
+    pub fn from_body_str(body: &str) -> Result<WalletAddress, WalletAddressParseError> {
+        WalletAddress::from_encoded(body, 0, &[0x00], ALL_SCHEMES)
+    }
+
+    /// Decodes the base-58 string after the first `prefix_len` bytes of the given string, and
+    /// creates the wallet address from it, verifying it.
+    ///
+    /// The whole string is used for the error messages, and for the position of invalid
+    /// characters.
+    fn from_encoded(s: &str,
+                    prefix_len: usize,
+                    allowed: &[u8],
+                    schemes: &[ChecksumScheme])
+                    -> Result<WalletAddress, WalletAddressParseError> {
+        let bytes = match decode_base58(s[prefix_len..].as_bytes()) {
+            Ok(Some(b)) => b,
+            Ok(None) => {
+                return Err(WalletAddressParseError::new(s,
+                                                        &format!("the address does not encode \
+                                                                  {} bytes",
+                                                                 WALLET_ADDRESS_LEN + 2),
+                                                        None));
+            }
+            Err(FromBase58Error::InvalidBase58Byte(c, i)) => {
+                let new_error = FromBase58Error::InvalidBase58Byte(c, i + prefix_len);
+                let description = match ambiguous_char_hint(c) {
+                    Some(hint) => {
+                        format!("the character {:?} at position {} is not used in wallet \
+                                 addresses, since it can be confused with other characters, \
+                                 it might be {}",
+                                c as char,
+                                i + prefix_len,
+                                hint)
+                    }
+                    None => format!("the address is not a valid base-58 encoded string: {}",
+                                    new_error),
+                };
+                return Err(WalletAddressParseError::new(s, &description, Some(new_error)));
+            }
+        };
+        WalletAddress::from_checked_bytes(s, &bytes, allowed, schemes)
+    }
+
+    /// Creates a wallet address from its bytes followed by its checksum, verifying that the
+    /// checksum matches one of the given schemes and that the version byte is one of the allowed
+    /// ones.
+    ///
+    /// The given string is only used for the error messages.
+    fn from_checked_bytes(s: &str,
+                          bytes: &[u8; WALLET_ADDRESS_LEN + 2],
+                          allowed: &[u8],
+                          schemes: &[ChecksumScheme])
+                          -> Result<WalletAddress, WalletAddressParseError> {
+        if !allowed.contains(&bytes[0]) {
+            let description = if allowed.len() == 1 {
+                format!("the first byte of the address is not 0x{:02X}", allowed[0])
+            } else {
+                format!("the version byte 0x{:02X} of the address is not allowed", bytes[0])
+            };
+            return Err(WalletAddressParseError::new(s, &description, None));
+        }
+
+        // The address is accepted if any of the schemes matches, so each accepted scheme adds
+        // the typos it misses to the ones that go undetected. The order of the schemes does not
+        // change the result.
+        let address_bytes = &bytes[..WALLET_ADDRESS_LEN];
+        let checksum_bytes = &bytes[WALLET_ADDRESS_LEN..WALLET_ADDRESS_LEN + 2];
+        if !schemes.iter().any(|&scheme| checksum(address_bytes, scheme) == checksum_bytes) {
+            Err(WalletAddressParseError::new(s, "checksum fail", None))
+        } else {
+            let mut address = [0u8; WALLET_ADDRESS_LEN];
+            address.clone_from_slice(address_bytes);
+            Ok(WalletAddress { address: address })
+        }
+    }
+
     /// Returns the wallet address bytes.
     ///
     /// This could be useful to store the bytes in databases where space can be an issue, or where
@@ -98,54 +447,292 @@ impl WalletAddress {
     pub fn get_raw(&self) -> &[u8] {
         &self.address
     }
-}
 
-impl From<[u8; WALLET_ADDRESS_LEN]> for WalletAddress {
-    fn from(other: [u8; WALLET_ADDRESS_LEN]) -> WalletAddress {
-        WalletAddress { address: other }
+    /// Returns the checksum bytes of the wallet address.
+    ///
+    /// These are the two bytes appended to the address before encoding it in base-58, and can be
+    /// used by protocols that transmit the raw address and its checksum separately.
+    ///
+    /// ```
+    /// use fractal_utils::WalletAddress;
+    ///
+    /// let addr = WalletAddress::from_data([0x00, 0x11, 0x2A, 0x44, 0xCD, 0xFF, 0xE0]);
+    /// assert_eq!(addr.checksum_bytes(), [0xAD, 0x07]);
+    /// ```
+    pub fn checksum_bytes(&self) -> [u8; 2] {
+        checksum(&self.address, ChecksumScheme::Xor)
     }
-}
 
-impl FromStr for WalletAddress {
-    type Err = WalletAddressParseError;
-    fn from_str(s: &str) -> Result<WalletAddress, WalletAddressParseError> {
-        if &s[0..2] != "fr" {
+    /// Returns the string representation of the wallet address using the given checksum scheme.
+    ///
+    /// The `Display` implementation uses the legacy `ChecksumScheme::Xor` scheme. Both schemes
+    /// produce strings that can be parsed back with `FromStr`:
+    ///
+    /// ```
+    /// use fractal_utils::wallet_address::{WalletAddress, ChecksumScheme};
+    ///
+    /// let addr = WalletAddress::from_data([0x00, 0x11, 0x2A, 0x44, 0xCD, 0xFF, 0xE0]);
+    /// let addr_str = addr.to_string_with_scheme(ChecksumScheme::Fletcher);
+    /// assert_eq!(addr_str.parse::<WalletAddress>().unwrap(), addr);
+    /// ```
+    pub fn to_string_with_scheme(&self, scheme: ChecksumScheme) -> String {
+        let mut buf = [0u8; ENCODED_MAX_LEN];
+        encode(&self.address, scheme, &mut buf).to_owned()
+    }
+
+    /// Returns the string representation of the wallet address split in groups of characters.
+    ///
+    /// The base-58 encoded part of the address is split in groups of `group` characters, with the
+    /// given separator before each of them. This makes addresses easier to read aloud or type, and
+    /// they can be parsed back with `from_grouped_str()`. A `group` of zero does not split the
+    /// address:
+    ///
+    /// ```
+    /// use fractal_utils::{WalletAddress, WALLET_ADDRESS_LEN};
+    ///
+    /// let addr = WalletAddress::from_data([0u8; WALLET_ADDRESS_LEN]);
+    /// assert_eq!(addr.to_grouped_string(4, '-'), "fr-1111-1111-1");
+    /// assert_eq!(addr.to_grouped_string(0, '-'), "fr111111111");
+    /// ```
+    pub fn to_grouped_string(&self, group: usize, sep: char) -> String {
+        let mut buf = [0u8; ENCODED_MAX_LEN];
+        let encoded = encode(&self.address, ChecksumScheme::Xor, &mut buf);
+        if group == 0 {
+            return encoded.to_owned();
+        }
+
+        let body = &encoded.as_bytes()[2..];
+        let mut grouped = String::with_capacity(encoded.len() + body.len() / group + 1);
+        grouped.push_str("fr");
+        for chunk in body.chunks(group) {
+            grouped.push(sep);
+            // Base-58 characters are ASCII, so each byte is a character.
+            grouped.extend(chunk.iter().map(|&b| b as char));
+        }
+        grouped
+    }
+
+    /// Parses a wallet address split in groups of characters by the given separator.
+    ///
+    /// The address must start with the `fr` prefix, and the separators are removed from the rest
+    /// of it before parsing, so the groups can have any size. The separator can't be a base-58
+    /// character, since it could not be told apart from the address. Error messages refer to the
+    /// address without separators:
+    ///
+    /// ```
+    /// use fractal_utils::{WalletAddress, WALLET_ADDRESS_LEN};
+    ///
+    /// let addr = WalletAddress::from_grouped_str("fr-1111-1111-1", '-').unwrap();
+    /// assert_eq!(addr, WalletAddress::from_data([0u8; WALLET_ADDRESS_LEN]));
+    ///
+    /// assert!(WalletAddress::from_grouped_str("f-r-1111-1111-1", '-').is_err());
+    /// assert!(WalletAddress::from_grouped_str("fr1111x1111x1", 'x').is_err());
+    /// ```
+    pub fn from_grouped_str(s: &str, sep: char) -> Result<WalletAddress, WalletAddressParseError> {
+        if sep.is_ascii() && BASE58_ALPHABET.contains(&(sep as u8)) {
             return Err(WalletAddressParseError::new(s,
-                                                    "the address does not start with \"fr\"",
+                                                    &format!("the separator {:?} is a base-58 \
+                                                              character",
+                                                             sep),
                                                     None));
         }
-        let bytes = match s[2..].from_base58() {
-            Ok(b) => b,
-            Err(FromBase58Error::InvalidBase58Byte(c, i)) => {
-                let new_error = FromBase58Error::InvalidBase58Byte(c, i + 2);
-                return Err(WalletAddressParseError::new(s,
-                                                        &format!("the address is not a valid \
-                                                                  base-58 encoded string: {}",
-                                                                 new_error),
-                                                        Some(new_error)));
-            }
-        };
-        if bytes[0] != 0x00 {
+        if !s.starts_with("fr") {
             return Err(WalletAddressParseError::new(s,
-                                                    "the first byte of the address is not 0x00",
+                                                    "the address does not start with \"fr\"",
                                                     None));
         }
+        let mut stripped = String::with_capacity(s.len());
+        stripped.push_str("fr");
+        stripped.extend(s[2..].chars().filter(|&c| c != sep));
+        stripped.parse()
+    }
 
-        let mut checksum = [0u8; 2];
-        for byte in &bytes[..WALLET_ADDRESS_LEN] {
-            checksum[0] ^= *byte;
-            checksum[1] ^= checksum[0];
+    /// Compares two wallet addresses by their string representation.
+    ///
+    /// This is the order in which addresses appear when their strings are sorted alphabetically,
+    /// which can differ from the byte order of the `Ord` implementation:
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use fractal_utils::WalletAddress;
+    ///
+    /// let one = WalletAddress::from_data([0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01]);
+    /// let max = WalletAddress::from_data([0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF]);
+    /// assert_eq!(one.to_string(), "fr111111LZN");
+    /// assert_eq!(max.to_string(), "fr1111112UzHL");
+    ///
+    /// assert_eq!(one.cmp(&max), Ordering::Less);
+    /// assert_eq!(one.cmp_display(&max), Ordering::Greater);
+    /// ```
+    pub fn cmp_display(&self, other: &WalletAddress) -> Ordering {
+        let mut buf = [0u8; ENCODED_MAX_LEN];
+        let mut other_buf = [0u8; ENCODED_MAX_LEN];
+        encode(&self.address, ChecksumScheme::Xor, &mut buf)
+            .cmp(encode(&other.address, ChecksumScheme::Xor, &mut other_buf))
+    }
+}
+
+/// Wallet address checksum scheme.
+///
+/// Wallet addresses were originally checksummed with the `Xor` scheme, which can't detect some
+/// common errors, such as swapping two equal bytes or changing a byte twice by the same bits. The
+/// `Fletcher` scheme uses position dependent modular sums, which detect them. Both schemes produce
+/// two checksum bytes, so the string representations have the same length.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ChecksumScheme {
+    /// Legacy checksum: the first byte is the `XOR` of all the bytes of the address, and the
+    /// second one the `XOR` of the first one after each byte.
+    Xor,
+    /// Fletcher-16 checksum: the first byte is the sum of all the bytes of the address modulo
+    /// 255, and the second one the sum modulo 255 of the first one after each byte.
+    Fletcher,
+}
+
+/// The checksum schemes accepted by `FromStr`, in the order they are checked.
+const ALL_SCHEMES: &[ChecksumScheme] = &[ChecksumScheme::Xor, ChecksumScheme::Fletcher];
+
+/// Computes the checksum of the given wallet address bytes with the given scheme.
+fn checksum(address: &[u8], scheme: ChecksumScheme) -> [u8; 2] {
+    match scheme {
+        ChecksumScheme::Xor => {
+            let mut checksum = [0u8; 2];
+            for byte in address {
+                checksum[0] ^= *byte;
+                checksum[1] ^= checksum[0];
+            }
+            checksum
         }
+        ChecksumScheme::Fletcher => {
+            let mut sums = [0u16; 2];
+            for byte in address {
+                sums[0] = (sums[0] + *byte as u16) % 255;
+                sums[1] = (sums[1] + sums[0]) % 255;
+            }
+            [sums[0] as u8, sums[1] as u8]
+        }
+    }
+}
 
-        if checksum[0] != bytes[WALLET_ADDRESS_LEN] ||
-           checksum[1] != bytes[WALLET_ADDRESS_LEN + 1] {
-            Err(WalletAddressParseError::new(s, "checksum fail", None))
-        } else {
-            let mut address = [0u8; WALLET_ADDRESS_LEN];
-            address.clone_from_slice(&bytes[..WALLET_ADDRESS_LEN]);
-            Ok(WalletAddress::from_data(address))
+/// The base-58 alphabet, the same one used by Bitcoin addresses.
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Returns the likely intended character for the characters excluded from the base-58 alphabet
+/// because they look like others.
+fn ambiguous_char_hint(byte: u8) -> Option<&'static str> {
+    match byte {
+        b'0' | b'O' => Some("an 'o'"),
+        b'I' | b'l' => Some("a '1'"),
+        _ => None,
+    }
+}
+
+/// The maximum length of a wallet address string.
+///
+/// That is the `fr` prefix, a `1` for each leading zero byte and at most 13 base-58 digits, since
+/// 58¹³ is bigger than the 72 bits of an address with its checksum.
+const ENCODED_MAX_LEN: usize = 2 + WALLET_ADDRESS_LEN + 2 + 13;
+
+/// Returns the value of the given base-58 digit, or `None` if it's not in the alphabet.
+fn base58_digit(byte: u8) -> Option<u8> {
+    match byte {
+        b'1'..=b'9' => Some(byte - b'1'),
+        b'A'..=b'H' => Some(byte - b'A' + 9),
+        b'J'..=b'N' => Some(byte - b'J' + 17),
+        b'P'..=b'Z' => Some(byte - b'P' + 22),
+        b'a'..=b'k' => Some(byte - b'a' + 33),
+        b'm'..=b'z' => Some(byte - b'm' + 44),
+        _ => None,
+    }
+}
+
+/// Encodes the given address with its checksum into the buffer, returning the address string.
+///
+/// An address with its checksum fits in a `u128`, so it's encoded with native arithmetic instead
+/// of the arbitrary precision one of `rust-base58`, without allocating. The result is the same.
+fn encode<'b>(address: &[u8; WALLET_ADDRESS_LEN],
+              scheme: ChecksumScheme,
+              buf: &'b mut [u8; ENCODED_MAX_LEN])
+              -> &'b str {
+    let checksum = checksum(address, scheme);
+    let mut value = 0u128;
+    for byte in address.iter().chain(checksum.iter()) {
+        value = value << 8 | *byte as u128;
+    }
+
+    // Digits are written from the end of the buffer, since the highest ones come last.
+    let mut start = buf.len();
+    while value > 0 {
+        start -= 1;
+        buf[start] = BASE58_ALPHABET[(value % 58) as usize];
+        value /= 58;
+    }
+    for _ in address.iter().chain(checksum.iter()).take_while(|b| **b == 0) {
+        start -= 1;
+        buf[start] = BASE58_ALPHABET[0];
+    }
+    start -= 2;
+    buf[start..start + 2].clone_from_slice(b"fr");
+    str::from_utf8(&buf[start..]).unwrap()
+}
+
+/// Decodes the given base-58 string into an address with its checksum.
+///
+/// It returns `None` if the string does not encode exactly `WALLET_ADDRESS_LEN + 2` bytes. Errors
+/// are the same as those of `rust-base58`, that reports the last invalid byte of the string.
+fn decode_base58(s: &[u8]) -> Result<Option<[u8; WALLET_ADDRESS_LEN + 2]>, FromBase58Error> {
+    let mut value = Some(0u128);
+    let mut invalid = None;
+    for (i, byte) in s.iter().enumerate() {
+        match base58_digit(*byte) {
+            Some(digit) => {
+                value = value.and_then(|v| v.checked_mul(58))
+                    .and_then(|v| v.checked_add(digit as u128));
+            }
+            None => invalid = Some(FromBase58Error::InvalidBase58Byte(*byte, i)),
         }
+    }
+    if let Some(e) = invalid {
+        return Err(e);
+    }
 
+    // If the value does not fit in a `u128` it's too long to be an address.
+    let value = match value {
+        Some(v) => v,
+        None => return Ok(None),
+    };
+    let zeros = s.iter().take_while(|b| **b == BASE58_ALPHABET[0]).count();
+    let value_len = (128 - value.leading_zeros() as usize + 7) / 8;
+    if zeros + value_len != WALLET_ADDRESS_LEN + 2 {
+        return Ok(None);
+    }
+
+    let mut bytes = [0u8; WALLET_ADDRESS_LEN + 2];
+    for (i, byte) in bytes.iter_mut().rev().enumerate() {
+        *byte = (value >> (8 * i)) as u8;
+    }
+    Ok(Some(bytes))
+}
+
+impl From<[u8; WALLET_ADDRESS_LEN]> for WalletAddress {
+    fn from(other: [u8; WALLET_ADDRESS_LEN]) -> WalletAddress {
+        WalletAddress { address: other }
+    }
+}
+
+/// Wallet addresses can be compared with raw addresses, without constructing a `WalletAddress`.
+impl PartialEq<[u8; WALLET_ADDRESS_LEN]> for WalletAddress {
+    fn eq(&self, other: &[u8; WALLET_ADDRESS_LEN]) -> bool {
+        self.address == *other
+    }
+}
+
+/// Wallet addresses are parsed from their string representation, with the `0x00` version byte and
+/// a checksum of any of the `ChecksumScheme`s. Accepting both schemes lets more mistyped addresses
+/// through than the legacy scheme alone; `WalletAddress::from_str_with_scheme()` accepts only one.
+impl FromStr for WalletAddress {
+    type Err = WalletAddressParseError;
+    fn from_str(s: &str) -> Result<WalletAddress, WalletAddressParseError> {
+        WalletAddress::from_str_with_version(s, &[0x00])
     }
 }
 
@@ -158,17 +745,145 @@ impl json::ToJson for WalletAddress {
     }
 }
 
+#[cfg(feature = "schemars")]
+/// The JSON schema of the `WalletAddress` type describes it as a string starting with `fr`
+/// followed by base-58 characters.
+impl JsonSchema for WalletAddress {
+    fn schema_name() -> String {
+        String::from("WalletAddress")
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        let schema = SchemaObject {
+            metadata: Some(Box::new(Metadata {
+                description: Some(String::from("Fractal Global wallet address.")),
+                ..Default::default()
+            })),
+            instance_type: Some(InstanceType::String.into()),
+            string: Some(Box::new(StringValidation {
+                pattern: Some(String::from("^fr[1-9A-HJ-NP-Za-km-z]+$")),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        schema.into()
+    }
+}
+
 impl fmt::Display for WalletAddress {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut arr = [0u8; WALLET_ADDRESS_LEN + 2];
-        arr[0..WALLET_ADDRESS_LEN].clone_from_slice(&self.address);
+        let mut buf = [0u8; ENCODED_MAX_LEN];
+        f.write_str(encode(&self.address, ChecksumScheme::Xor, &mut buf))
+    }
+}
+
+/// Formats raw wallet addresses loaded from storage, computing their checksums.
+///
+/// Each raw address is validated to start with the `0x00` byte, and formatted the same way as
+/// `WalletAddress` does with the `Display` trait. Invalid addresses produce an error in their
+/// position of the result, so that the rest of the addresses can still be used:
+///
+/// ```
+/// use fractal_utils::wallet_address::{display_all, WALLET_ADDRESS_LEN};
+///
+/// let strings = display_all(&[[0u8; WALLET_ADDRESS_LEN], [1u8; WALLET_ADDRESS_LEN]]);
+/// assert_eq!(strings[0].as_ref().unwrap(), "fr111111111");
+/// assert!(strings[1].is_err());
+/// ```
+pub fn display_all(addrs: &[[u8; WALLET_ADDRESS_LEN]])
+                   -> Vec<Result<String, WalletAddressParseError>> {
+    addrs.iter()
+        .map(|addr| if addr[0] == 0x00 {
+            Ok(format!("{}", WalletAddress::from_data(*addr)))
+        } else {
+            Err(WalletAddressParseError::new(&format!("{:?}", addr)[..],
+                                             "the first byte of the address is not 0x00",
+                                             None))
+        })
+        .collect()
+}
 
-        for byte in &self.address {
-            arr[WALLET_ADDRESS_LEN] ^= *byte;
-            arr[WALLET_ADDRESS_LEN + 1] ^= arr[WALLET_ADDRESS_LEN];
+/// Returns test vectors of raw wallet addresses and their expected string representations.
+///
+/// These are meant for implementations of the wallet address format in other languages, which
+/// can check that they produce the same strings. They include edge cases as the all zeros
+/// address, the maximum address and addresses with leading zero bytes after the version byte.
+///
+/// ```
+/// use fractal_utils::WalletAddress;
+/// use fractal_utils::wallet_address::test_vectors;
+///
+/// for (raw, expected) in test_vectors() {
+///     assert_eq!(WalletAddress::from_data(raw).to_string(), expected);
+/// }
+/// ```
+#[cfg(feature = "test-vectors")]
+pub fn test_vectors() -> Vec<([u8; WALLET_ADDRESS_LEN], &'static str)> {
+    vec![([0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], "fr111111111"),
+         ([0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01], "fr111111LZN"),
+         ([0x00, 0x00, 0x11, 0x2A, 0x44, 0xCD, 0xFF], "fr11ejDcgqtxy"),
+         ([0x00, 0x11, 0x2A, 0x44, 0xCD, 0xFF, 0xE0], "fr13sXLgWsfk3Q"),
+         ([0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00], "fr1NQm6nKp8zzB"),
+         ([0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF], "fr1jpXCZedGL5t")]
+}
+
+/// Wallet address with a memoized string representation.
+///
+/// Displaying a `WalletAddress` computes its checksum and base-58 encoding every time. This
+/// wrapper computes the string the first time it's needed and keeps it, so that printing the same
+/// address repeatedly only costs a copy of the cached string:
+///
+/// ```
+/// use fractal_utils::wallet_address::{CachedWalletAddress, WalletAddress, WALLET_ADDRESS_LEN};
+///
+/// let addr = CachedWalletAddress::new(WalletAddress::from_data([0u8; WALLET_ADDRESS_LEN]));
+/// assert_eq!(&*addr.to_cached_string(), "fr111111111");
+/// assert_eq!(format!("{}", addr), "fr111111111");
+/// ```
+///
+/// The tradeoff is memory: each cached address keeps a heap allocated string alive for as long as
+/// the wrapper or any of the returned `Rc<str>` exist, instead of the 7 bytes of the address. It
+/// should only be used for addresses that are displayed often. Since it uses `Rc`, it can't be
+/// shared between threads.
+#[derive(Debug, Clone)]
+pub struct CachedWalletAddress {
+    address: WalletAddress,
+    string: RefCell<Option<Rc<str>>>,
+}
+
+impl CachedWalletAddress {
+    /// Creates a new cached wallet address. The string will be computed when first needed.
+    pub fn new(address: WalletAddress) -> CachedWalletAddress {
+        CachedWalletAddress {
+            address: address,
+            string: RefCell::new(None),
+        }
+    }
+
+    /// Returns the wrapped wallet address.
+    pub fn get_address(&self) -> &WalletAddress {
+        &self.address
+    }
+
+    /// Returns the string representation of the wallet address, computing it only the first time.
+    pub fn to_cached_string(&self) -> Rc<str> {
+        let mut string = self.string.borrow_mut();
+        if string.is_none() {
+            *string = Some(Rc::from(format!("{}", self.address)));
         }
+        string.as_ref().unwrap().clone()
+    }
+}
 
-        write!(f, "fr{}", arr.to_base58())
+impl From<WalletAddress> for CachedWalletAddress {
+    fn from(address: WalletAddress) -> CachedWalletAddress {
+        CachedWalletAddress::new(address)
+    }
+}
+
+impl fmt::Display for CachedWalletAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_cached_string())
     }
 }
 
@@ -7,15 +7,44 @@
     unused_qualifications, unused_results, variant_size_differences)]
 
 extern crate rand;
+extern crate rust_base58;
+#[macro_use]
 extern crate fractal_utils;
+#[cfg(feature = "schemars")]
+extern crate schemars;
+#[cfg(feature = "serde_json")]
+extern crate serde_json;
+#[cfg(feature = "serde_json")]
+extern crate rustc_serialize;
 
 use std::str::FromStr;
 use std::u64;
+use std::f64;
+use std::rc::Rc;
+use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::error::Error;
+use std::io::Cursor;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
 
 use rand::{Rng, thread_rng};
+use rust_base58::{ToBase58, FromBase58};
 
-use fractal_utils::wallet_address::{WalletAddress, WALLET_ADDRESS_LEN};
-use fractal_utils::amount::Amount;
+use fractal_utils::wallet_address::{display_all, WalletAddress, CachedWalletAddress, ChecksumScheme,
+                                    WALLET_ADDRESS_LEN};
+use fractal_utils::amount::{Amount, AmountList, LossyAmount, RoundingMode, ReconcileError,
+                             InsufficientFunds};
+use fractal_utils::location::{Address, AddressError, AddressField, AddressLengthLimits,
+                              AddressQuery, UsState};
+use fractal_utils::error::FractalError;
+use fractal_utils::CURRENCY_SYMBOL;
+
+#[cfg(feature = "schemars")]
+use schemars::gen::SchemaGenerator;
+#[cfg(feature = "schemars")]
+use schemars::schema::InstanceType;
 
 #[cfg(test)]
 #[test]
@@ -157,3 +186,1936 @@ fn it_amount_ops() {
     assert_eq!(amount, Amount::from_repr(2_345));
     assert_eq!(amount % 1u32, Amount::from_repr(345));
 }
+
+#[test]
+fn it_amount_rounding_mode() {
+    let amount = Amount::from_repr(1_500); // 1.500
+    assert_eq!(format!("{:.0}", amount), "2");
+    assert_eq!(format!("{:.0}", amount.display_rounded(RoundingMode::HalfUp)), "2");
+    assert_eq!(format!("{:.0}", amount.display_rounded(RoundingMode::HalfEven)), "2");
+
+    let amount = Amount::from_repr(2_500); // 2.500
+    assert_eq!(format!("{:.0}", amount), "3");
+    assert_eq!(format!("{:.0}", amount.display_rounded(RoundingMode::HalfUp)), "3");
+    assert_eq!(format!("{:.0}", amount.display_rounded(RoundingMode::HalfEven)), "2");
+
+    let amount = Amount::from_repr(2_450); // 2.450
+    assert_eq!(format!("{:.1}", amount), "2.5");
+    assert_eq!(format!("{:.1}", amount.display_rounded(RoundingMode::HalfEven)), "2.4");
+
+    let amount = Amount::from_repr(2_451); // 2.451
+    assert_eq!(format!("{:.1}", amount.display_rounded(RoundingMode::HalfEven)), "2.5");
+    assert_eq!(format!("{:.3}", amount.display_rounded(RoundingMode::HalfEven)), "2.451");
+
+    // Rounding carries to the units.
+    let amount = Amount::from_repr(960); // 0.960
+    assert_eq!(format!("{:.1}", amount), "1.0");
+    let amount = Amount::from_repr(1_995); // 1.995
+    assert_eq!(format!("{:.2}", amount), "2.00");
+    assert_eq!(format!("{:06.2}", amount.display_rounded(RoundingMode::HalfEven)), "002.00");
+}
+
+#[test]
+fn it_amount_parse_units_boundary() {
+    let max_units = u64::MAX / 1_000;
+    let amount: Amount = format!("{}", max_units).parse().unwrap();
+    assert_eq!(amount, Amount::from_repr(max_units * 1_000));
+
+    let amount: Result<Amount, _> = format!("{}", max_units + 1).parse();
+    assert!(amount.is_err());
+
+    let amount: Result<Amount, _> = format!("{}", u64::MAX).parse();
+    assert!(amount.unwrap_err().to_string().contains("it is too big"));
+
+    // Bigger than what fits in an `u64` is still reported as too big, not as invalid.
+    let amount: Result<Amount, _> = "18446744073709551616".parse();
+    assert!(amount.unwrap_err().to_string().contains("it is too big"));
+    let amount: Result<Amount, _> = "1000000000000000000000000000000000000000000".parse();
+    assert!(amount.unwrap_err().to_string().contains("it is too big"));
+
+    let amount: Result<Amount, _> = "17a".parse();
+    assert!(amount.unwrap_err().to_string().contains("not a valid u64 number"));
+}
+
+#[test]
+fn it_amount_parse_max_decimals_boundary() {
+    let max_units = u64::MAX / 1_000;
+    let max_decimals = u64::MAX % 1_000;
+
+    let amount: Amount = format!("{}.{:03}", max_units, max_decimals).parse().unwrap();
+    assert_eq!(amount, Amount::max_value());
+    let amount: Result<Amount, _> = format!("{}.{:03}", max_units, max_decimals + 1).parse();
+    assert!(amount.is_err());
+
+    // Extra decimals rounding down or up to the limit.
+    let amount: Amount = format!("{}.{:03}4", max_units, max_decimals).parse().unwrap();
+    assert_eq!(amount, Amount::max_value());
+    let amount: Result<Amount, _> = format!("{}.{:03}5", max_units, max_decimals).parse();
+    assert!(amount.is_err());
+
+    // Decimals carrying into the units.
+    let amount: Amount = format!("{}.9995", max_units - 1).parse().unwrap();
+    assert_eq!(amount, Amount::from_repr(max_units * 1_000));
+    let amount: Result<Amount, _> = format!("{}.9995", max_units).parse();
+    assert!(amount.is_err());
+
+    let amount: Amount = format!("{}", Amount::max_value()).parse().unwrap();
+    assert_eq!(amount.get_repr() % 1_000, max_decimals);
+}
+
+#[test]
+fn it_cached_walletaddress() {
+    let mut random_addr = [0u8; WALLET_ADDRESS_LEN];
+    thread_rng().fill_bytes(&mut random_addr[1..]);
+    let addr = WalletAddress::from_data(random_addr);
+    let cached = CachedWalletAddress::from(addr);
+
+    let first = cached.to_cached_string();
+    let second = cached.to_cached_string();
+    assert_eq!(first, second);
+    assert!(Rc::ptr_eq(&first, &second));
+    assert_eq!(&*first, format!("{}", addr));
+    assert_eq!(format!("{}", cached), format!("{}", addr));
+    assert_eq!(cached.get_address(), &addr);
+}
+
+#[test]
+fn it_address_default_timezone() {
+    let address = Address::new("Unter den Linden 1", None, "Berlin", "Berlin", "10117", "DE");
+    assert_eq!(address.default_timezone(), Some("Europe/Berlin"));
+
+    let address = Address::new("Unter den Linden 1", None, "Berlin", "Berlin", "10117", "de");
+    assert_eq!(address.default_timezone(), Some("Europe/Berlin"));
+
+    let address = Address::new("123 Main St",
+                               Some("Apt 4"),
+                               "Springfield",
+                               "IL",
+                               "62704",
+                               "US");
+    assert_eq!(address.default_timezone(), None);
+
+    let address = Address::new("Calle Mayor 1", None, "Nowhere", "Nowhere", "00000", "XX");
+    assert_eq!(address.default_timezone(), None);
+}
+
+#[test]
+fn it_amount_parse_with_unit() {
+    let amount = Amount::from_str_with_unit("175.646 credits").unwrap();
+    assert_eq!(amount, Amount::from_repr(175_646));
+
+    let amount = Amount::from_str_with_unit("1 Credit").unwrap();
+    assert_eq!(amount, Amount::from_repr(1_000));
+
+    let amount = Amount::from_str_with_unit(&format!("175.646{}", CURRENCY_SYMBOL)).unwrap();
+    assert_eq!(amount, Amount::from_repr(175_646));
+
+    let amount = Amount::from_str_with_unit(&format!("175.646 {}", CURRENCY_SYMBOL)).unwrap();
+    assert_eq!(amount, Amount::from_repr(175_646));
+
+    let amount = Amount::from_str_with_unit("175.646").unwrap();
+    assert_eq!(amount, Amount::from_repr(175_646));
+
+    let amount = Amount::from_str_with_unit("175.646 euros");
+    assert!(amount.unwrap_err().to_string().contains("\"euros\" is not a known"));
+
+    let amount = Amount::from_str_with_unit(&format!("175.646 {0}{0}", CURRENCY_SYMBOL));
+    assert!(amount.is_err());
+
+    let amount = Amount::from_str_with_unit("credits");
+    assert!(amount.is_err());
+
+    // Any number accepted by `FromStr` is accepted before the unit.
+    let amount = Amount::from_str_with_unit("1,234.5 credits").unwrap();
+    assert_eq!(amount, Amount::from_repr(1_234_500));
+    let amount = Amount::from_str_with_unit("+1 credits").unwrap();
+    assert_eq!(amount, Amount::from_repr(1_000));
+    let amount = Amount::from_str_with_unit(&format!("+12,345{}", CURRENCY_SYMBOL)).unwrap();
+    assert_eq!(amount, Amount::from_repr(12_345_000));
+    let amount = Amount::from_str_with_unit(" 1,000 CREDITS ").unwrap();
+    assert_eq!(amount, Amount::from_repr(1_000_000));
+
+    let amount = Amount::from_str_with_unit("1,23 credits");
+    assert!(amount.unwrap_err().to_string().contains("thousands separators"));
+    let amount = Amount::from_str_with_unit("+1 dollars");
+    assert!(amount.unwrap_err().to_string().contains("\"dollars\" is not a known"));
+}
+
+#[test]
+fn it_walletaddress_checksum_bytes() {
+    let addr = WalletAddress::from_data([0x00, 0x11, 0x2A, 0x44, 0xCD, 0xFF, 0xE0]);
+    assert_eq!(addr.checksum_bytes(), [0xAD, 0x07]);
+
+    let addr = WalletAddress::from_data([0u8; WALLET_ADDRESS_LEN]);
+    assert_eq!(addr.checksum_bytes(), [0x00, 0x00]);
+}
+
+#[test]
+fn it_amount_fee() {
+    let minimum = Amount::from_repr(100); // 0.1
+
+    // The percentage dominates.
+    let amount = Amount::from_repr(175_646); // 175.646
+    assert_eq!(amount.fee(200, minimum), Amount::from_repr(3_513)); // 3.51292
+    assert_eq!(amount.fee(10_000, minimum), amount);
+
+    // The minimum dominates.
+    let amount = Amount::from_repr(4_000); // 4
+    assert_eq!(amount.fee(200, minimum), minimum);
+    assert_eq!(Amount::min_value().fee(200, minimum), minimum);
+
+    // Rounding half up.
+    assert_eq!(Amount::from_repr(25).fee(200, Amount::min_value()), Amount::from_repr(1));
+    assert_eq!(Amount::from_repr(24).fee(200, Amount::min_value()), Amount::min_value());
+}
+
+#[test]
+fn it_amount_allocate() {
+    let total = Amount::from_repr(1_000_001);
+    let weights = [3, 7, 11, 0, 5];
+    let shares = total.allocate(&weights);
+    assert_eq!(shares.len(), weights.len());
+    assert_eq!(shares.iter().fold(0, |acc, a| acc + a.get_repr()), total.get_repr());
+    assert_eq!(shares[3], Amount::min_value());
+
+    let shares = Amount::from_repr(100).allocate(&[1, 1, 1]);
+    assert_eq!(shares,
+               vec![Amount::from_repr(34), Amount::from_repr(33), Amount::from_repr(33)]);
+
+    let shares = Amount::from_repr(10).allocate(&[1, 2]);
+    assert_eq!(shares, vec![Amount::from_repr(3), Amount::from_repr(7)]);
+
+    for _ in 0..50 {
+        let total = Amount::from_repr(thread_rng().gen());
+        let weights: Vec<u64> = (0..10).map(|_| thread_rng().gen_range(0, 1_000)).collect();
+        if weights.iter().all(|w| *w == 0) {
+            continue;
+        }
+        let shares = total.allocate(&weights);
+        let sum = shares.iter().fold(0u128, |acc, a| acc + a.get_repr() as u128);
+        assert_eq!(sum, total.get_repr() as u128);
+    }
+
+    assert!(Amount::min_value().allocate(&[]).is_empty());
+    assert_eq!(Amount::min_value().allocate(&[0, 0]), vec![Amount::min_value(); 2]);
+}
+
+#[test]
+#[should_panic]
+fn it_amount_allocate_no_weights() {
+    let _ = Amount::from_repr(1).allocate(&[0, 0]);
+}
+
+#[test]
+fn it_amount_compound() {
+    let amount = Amount::from_repr(1_000_000); // 1,000
+    assert_eq!(amount.compound(500, 0), Some(amount));
+    assert_eq!(amount.compound(500, 1), Some(Amount::from_repr(1_050_000)));
+    assert_eq!(amount.compound(500, 2), Some(Amount::from_repr(1_102_500)));
+    assert_eq!(amount.compound(500, 3), Some(Amount::from_repr(1_157_625)));
+    assert_eq!(amount.compound(0, 100), Some(amount));
+
+    // 10 at 3.33%: 10.333 and then 10.333 + 0.3440889 rounded.
+    let amount = Amount::from_repr(10_000);
+    assert_eq!(amount.compound(333, 2), Some(Amount::from_repr(10_677)));
+
+    assert_eq!(Amount::max_value().compound(1, 1), None);
+    assert_eq!(Amount::max_value().compound(0, 1), Some(Amount::max_value()));
+    assert_eq!(Amount::from_repr(u64::MAX / 2).compound(10_000, 1),
+               Some(Amount::from_repr(u64::MAX - 1)));
+    assert_eq!(Amount::from_repr(u64::MAX / 2 + 1).compound(10_000, 1), None);
+}
+
+#[test]
+fn it_walletaddress_checksum_schemes() {
+    let addr = WalletAddress::from_data([0x00, 0x11, 0x2A, 0x44, 0xCD, 0xFF, 0xE0]);
+    let legacy = addr.to_string_with_scheme(ChecksumScheme::Xor);
+    let fletcher = addr.to_string_with_scheme(ChecksumScheme::Fletcher);
+    assert_eq!(legacy, format!("{}", addr));
+    assert!(legacy != fletcher);
+
+    assert_eq!(WalletAddress::from_str(&legacy).unwrap(), addr);
+    assert_eq!(WalletAddress::from_str(&fletcher).unwrap(), addr);
+    assert_eq!(WalletAddress::from_str_with_scheme(&legacy, ChecksumScheme::Xor).unwrap(), addr);
+    assert!(WalletAddress::from_str_with_scheme(&legacy, ChecksumScheme::Fletcher).is_err());
+    assert!(WalletAddress::from_str_with_scheme(&fletcher, ChecksumScheme::Xor).is_err());
+    assert!(WalletAddress::from_str_with_scheme(&legacy[2..], ChecksumScheme::Xor).is_err());
+
+    for _ in 0..50 {
+        let mut random_addr = [0u8; WALLET_ADDRESS_LEN];
+        thread_rng().fill_bytes(&mut random_addr[1..]);
+        let addr = WalletAddress::from_data(random_addr);
+        let fletcher = addr.to_string_with_scheme(ChecksumScheme::Fletcher);
+        assert_eq!(WalletAddress::from_str(&fletcher).unwrap(), addr);
+    }
+
+    let wallet: Result<WalletAddress, _> = "fr111111112".parse();
+    assert!(wallet.is_err());
+}
+
+#[test]
+fn it_walletaddress_checksum_schemes_mutations() {
+    const ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+    // Counts the single character typos of random addresses that each parser accepts. Accepting
+    // both schemes lets through the typos any of them misses.
+    let (mut mutations, mut accepted_xor, mut accepted_any) = (0, 0, 0);
+    for _ in 0..200 {
+        let mut random_addr = [0u8; WALLET_ADDRESS_LEN];
+        thread_rng().fill_bytes(&mut random_addr[1..]);
+        let addr_str = WalletAddress::from_data(random_addr).to_string();
+        for pos in 2..addr_str.len() {
+            for c in ALPHABET.chars().filter(|&c| c != addr_str[pos..].chars().next().unwrap()) {
+                let mutated = format!("{}{}{}", &addr_str[..pos], c, &addr_str[pos + 1..]);
+                let xor = WalletAddress::from_str_with_scheme(&mutated, ChecksumScheme::Xor);
+                let any = mutated.parse::<WalletAddress>();
+                assert!(xor.is_err() || any.is_ok());
+                mutations += 1;
+                accepted_xor += xor.is_ok() as usize;
+                accepted_any += any.is_ok() as usize;
+            }
+        }
+    }
+    // About one in 200,000 typos passes the legacy checksum, and one in 40,000 passes any of the
+    // two checksums. The bounds leave room for the randomness of the sample.
+    assert!(accepted_xor <= mutations / 20_000);
+    assert!(accepted_any <= mutations / 5_000);
+}
+
+#[cfg(feature = "schemars")]
+#[test]
+fn it_amount_json_schema() {
+    let schema = SchemaGenerator::default().into_root_schema_for::<Amount>();
+    assert_eq!(schema.schema.instance_type, Some(InstanceType::Integer.into()));
+    assert!(schema.schema.metadata.unwrap().description.unwrap().contains("1,000"));
+}
+
+#[cfg(feature = "schemars")]
+#[test]
+fn it_walletaddress_json_schema() {
+    let schema = SchemaGenerator::default().into_root_schema_for::<WalletAddress>();
+    assert_eq!(schema.schema.instance_type, Some(InstanceType::String.into()));
+    assert_eq!(schema.schema.string.unwrap().pattern,
+               Some(String::from("^fr[1-9A-HJ-NP-Za-km-z]+$")));
+}
+
+#[cfg(feature = "schemars")]
+#[test]
+fn it_address_json_schema() {
+    let schema = SchemaGenerator::default().into_root_schema_for::<Address>();
+    assert_eq!(schema.schema.instance_type, Some(InstanceType::Object.into()));
+    let object = schema.schema.object.unwrap();
+    assert_eq!(object.properties.len(), 6);
+    assert!(object.properties.contains_key("address2"));
+    assert!(!object.required.contains("address2"));
+    assert!(object.required.contains("zip"));
+}
+
+#[test]
+fn it_amount_parse_error_position() {
+    let error = "175.837.9239".parse::<Amount>().unwrap_err();
+    assert_eq!(error.position(), Some(7));
+    assert!(error.to_string().ends_with("(at byte 7)"));
+
+    let error = ".098320.2930".parse::<Amount>().unwrap_err();
+    assert_eq!(error.position(), Some(7));
+
+    let error = "17a5".parse::<Amount>().unwrap_err();
+    assert_eq!(error.position(), Some(2));
+
+    let error = "17a.5".parse::<Amount>().unwrap_err();
+    assert_eq!(error.position(), Some(2));
+
+    let error = "175.6x4".parse::<Amount>().unwrap_err();
+    assert_eq!(error.position(), Some(5));
+
+    let error = "175.".parse::<Amount>().unwrap_err();
+    assert_eq!(error.position(), None);
+
+    let error = format!("{}", u64::MAX).parse::<Amount>().unwrap_err();
+    assert_eq!(error.position(), None);
+    assert!(!error.to_string().contains("at byte"));
+}
+
+#[test]
+fn it_amount_div_rem() {
+    let amount = Amount::from_repr(10_500);
+    assert_eq!(amount.div_rem(&Amount::from_repr(3_000)),
+               Some((3, Amount::from_repr(1_500))));
+    assert_eq!(amount.div_rem(&Amount::from_repr(10_500)), Some((1, Amount::min_value())));
+    assert_eq!(amount.div_rem(&Amount::from_repr(20_000)), Some((0, amount)));
+    assert_eq!(amount.div_rem(&Amount::from_repr(1)), Some((10_500, Amount::min_value())));
+    assert_eq!(amount.div_rem(&Amount::min_value()), None);
+}
+
+#[test]
+fn it_walletaddress_generate_sequential() {
+    let addrs = WalletAddress::generate_sequential(0xFFFF_FF00, 1_000);
+    assert_eq!(addrs.len(), 1_000);
+    assert_eq!(addrs[0], WalletAddress::from_u64(0xFFFF_FF00).unwrap());
+
+    let strings: HashSet<String> = addrs.iter().map(|a| format!("{}", a)).collect();
+    assert_eq!(strings.len(), addrs.len());
+    for (addr, addr_str) in addrs.iter().zip(addrs.iter().map(|a| format!("{}", a))) {
+        assert_eq!(&WalletAddress::from_str(&addr_str).unwrap(), addr);
+    }
+
+    let last = (1 << 48) - 1;
+    assert_eq!(WalletAddress::generate_sequential(last, 1)[0].get_raw(),
+               &[0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+    assert!(WalletAddress::generate_sequential(0, 0).is_empty());
+}
+
+#[test]
+#[should_panic]
+fn it_walletaddress_generate_sequential_overflow() {
+    let _ = WalletAddress::generate_sequential((1 << 48) - 1, 2);
+}
+
+fn hash_of<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn it_amount_hash() {
+    let from_repr = Amount::from_repr(175_000);
+    let parsed: Amount = "175".parse().unwrap();
+    let parsed_decimals: Amount = "175.000".parse().unwrap();
+    let rounded: Amount = "174.9996".parse().unwrap();
+    assert_eq!(from_repr, parsed);
+    assert_eq!(hash_of(&from_repr), hash_of(&parsed));
+    assert_eq!(hash_of(&from_repr), hash_of(&parsed_decimals));
+    assert_eq!(hash_of(&from_repr), hash_of(&rounded));
+
+    let mut set = HashSet::new();
+    assert!(set.insert(from_repr));
+    assert!(!set.insert(parsed));
+    assert!(!set.insert(Amount::from_repr(100_000) + Amount::from_repr(75_000)));
+    assert!(set.insert(Amount::from_repr(175_001)));
+}
+
+#[test]
+fn it_address_validate_lengths() {
+    let address = Address::new("123 Main St", Some("Apt 4"), "Springfield", "IL", "62704", "US");
+    assert_eq!(address.validate_lengths(), Ok(()));
+
+    let long_city: String = (0..101).map(|_| 'a').collect();
+    let address = Address::new("123 Main St", None, &long_city, "IL", "62704", "US");
+    assert_eq!(address.validate_lengths(),
+               Err(AddressError::FieldTooLong {
+                   field: AddressField::City,
+                   max: 100,
+                   len: 101,
+               }));
+    assert!(address.validate_lengths().unwrap_err().to_string().contains("city"));
+
+    let limits = AddressLengthLimits { city: 101, ..Default::default() };
+    assert_eq!(address.validate_lengths_with(&limits), Ok(()));
+
+    // Lengths are in characters.
+    let address = Address::new("Gran Vía 1", Some("Ático"), "Málaga", "Málaga", "29001", "ES");
+    let limits = AddressLengthLimits { city: 6, address2: 4, ..Default::default() };
+    assert_eq!(address.validate_lengths_with(&limits),
+               Err(AddressError::FieldTooLong {
+                   field: AddressField::Address2,
+                   max: 4,
+                   len: 5,
+               }));
+}
+
+#[test]
+fn it_amount_from_base_units_str() {
+    let amount = Amount::from_base_units_str("175646000000000000000", 18).unwrap();
+    assert_eq!(amount, Amount::from_repr(175_646));
+
+    // Rounding half up the dropped base units.
+    let amount = Amount::from_base_units_str("175646499999999999999", 18).unwrap();
+    assert_eq!(amount, Amount::from_repr(175_646));
+    let amount = Amount::from_base_units_str("175646500000000000000", 18).unwrap();
+    assert_eq!(amount, Amount::from_repr(175_647));
+    let amount = Amount::from_base_units_str("500000000000000", 18).unwrap();
+    assert_eq!(amount, Amount::from_repr(1));
+    let amount = Amount::from_base_units_str("499999999999999", 18).unwrap();
+    assert_eq!(amount, Amount::min_value());
+
+    let amount = Amount::from_base_units_str("175646", 3).unwrap();
+    assert_eq!(amount, Amount::from_repr(175_646));
+    let amount = Amount::from_base_units_str("175", 0).unwrap();
+    assert_eq!(amount, Amount::from_repr(175_000));
+    let amount = Amount::from_base_units_str("000", 18).unwrap();
+    assert_eq!(amount, Amount::min_value());
+
+    // The maximum amount with 18 decimals, and just over it.
+    let max = format!("{}000000000000000", u64::MAX);
+    assert_eq!(Amount::from_base_units_str(&max, 18).unwrap(), Amount::max_value());
+    let over = format!("{}500000000000000", u64::MAX);
+    assert!(Amount::from_base_units_str(&over, 18).is_err());
+    let over = format!("{}000000000000000000000000", u64::MAX);
+    assert!(Amount::from_base_units_str(&over, 18).is_err());
+    assert!(Amount::from_base_units_str(&format!("{}", u64::MAX), 2).is_err());
+    // Rounding up the biggest `u128` does not overflow.
+    let error = Amount::from_base_units_str("3402823669209384634633746074317682114555", 4);
+    assert!(error.unwrap_err().to_string().contains("it is too big"));
+
+    let error = Amount::from_base_units_str("1756.46", 18).unwrap_err();
+    assert_eq!(error.position(), Some(4));
+    assert!(Amount::from_base_units_str("", 18).is_err());
+}
+
+#[test]
+fn it_walletaddress_display_all() {
+    let mut random_addr = [0u8; WALLET_ADDRESS_LEN];
+    thread_rng().fill_bytes(&mut random_addr[1..]);
+    let mut invalid_addr = random_addr;
+    invalid_addr[0] = 0x01;
+
+    let strings = display_all(&[[0u8; WALLET_ADDRESS_LEN], invalid_addr, random_addr]);
+    assert_eq!(strings.len(), 3);
+    assert_eq!(strings[0].as_ref().unwrap(), "fr111111111");
+    assert!(strings[1].as_ref().unwrap_err().to_string().contains("first byte"));
+    assert_eq!(strings[2].as_ref().unwrap(),
+               &format!("{}", WalletAddress::from_data(random_addr)));
+
+    assert!(display_all(&[]).is_empty());
+}
+
+#[test]
+fn it_amount_format_currency() {
+    let amount = Amount::from_repr(175_646);
+    assert_eq!(amount.format_currency(CURRENCY_SYMBOL, true, true),
+               format!("{} 175.646", CURRENCY_SYMBOL));
+    assert_eq!(amount.format_currency(CURRENCY_SYMBOL, true, false),
+               format!("{}175.646", CURRENCY_SYMBOL));
+    assert_eq!(amount.format_currency(CURRENCY_SYMBOL, false, true),
+               format!("175.646 {}", CURRENCY_SYMBOL));
+    assert_eq!(amount.format_currency(CURRENCY_SYMBOL, false, false),
+               format!("175.646{}", CURRENCY_SYMBOL));
+    assert_eq!(Amount::from_repr(30_000).format_currency('$', true, false), "$30");
+}
+
+#[test]
+fn it_amount_is_dust() {
+    let threshold = Amount::from_repr(100); // 0.1
+    assert!(Amount::from_repr(1).is_dust(threshold));
+    assert!(Amount::from_repr(99).is_dust(threshold));
+    assert!(!Amount::min_value().is_dust(threshold));
+    assert!(!Amount::from_repr(100).is_dust(threshold));
+    assert!(!Amount::from_repr(175_646).is_dust(threshold));
+    assert!(!Amount::from_repr(1).is_dust(Amount::min_value()));
+}
+
+#[test]
+fn it_amount_wrapping_ops() {
+    assert_eq!(Amount::max_value().wrapping_add(Amount::from_repr(1)), Amount::min_value());
+    assert_eq!(Amount::max_value().wrapping_add(Amount::from_repr(10)), Amount::from_repr(9));
+    assert_eq!(Amount::min_value().wrapping_sub(Amount::from_repr(1)), Amount::max_value());
+    assert_eq!(Amount::from_repr(10).wrapping_sub(Amount::from_repr(3)), Amount::from_repr(7));
+    assert_eq!(Amount::max_value().wrapping_mul(2), Amount::from_repr(u64::MAX - 1));
+    assert_eq!(Amount::from_repr(7_000).wrapping_mul(10), Amount::from_repr(70_000));
+}
+
+#[test]
+fn it_address_parse_single_line() {
+    let address = Address::parse_single_line("123 Main St, Springfield, IL, 62704, US").unwrap();
+    assert_eq!(address,
+               Address::new("123 Main St", None, "Springfield", "IL", "62704", "US"));
+
+    let address = Address::parse_single_line("123 Main St, Apt 4, Springfield, IL, 62704, US")
+        .unwrap();
+    assert_eq!(address,
+               Address::new("123 Main St", Some("Apt 4"), "Springfield", "IL", "62704", "US"));
+
+    let address = Address::parse_single_line("123 Main St,,Springfield,IL,62704,US").unwrap();
+    assert_eq!(address.get_address2(), None);
+    assert_eq!(address.get_country(), "US");
+
+    assert_eq!(Address::parse_single_line("123 Main St, Springfield, IL, US"),
+               Err(AddressError::InvalidFieldCount { found: 4 }));
+    assert_eq!(Address::parse_single_line("a, b, c, d, e, f, g"),
+               Err(AddressError::InvalidFieldCount { found: 7 }));
+}
+
+#[test]
+fn it_amount_reconcile() {
+    let credits = [Amount::from_repr(10_000), Amount::from_repr(5_500)];
+
+    // Balanced.
+    let debits = [Amount::from_repr(15_000), Amount::from_repr(500)];
+    assert_eq!(Amount::reconcile(&credits, &debits), Ok(Amount::min_value()));
+
+    // Credit surplus.
+    let debits = [Amount::from_repr(3_000)];
+    assert_eq!(Amount::reconcile(&credits, &debits), Ok(Amount::from_repr(12_500)));
+    assert_eq!(Amount::reconcile(&credits, &[]), Ok(Amount::from_repr(15_500)));
+
+    // Debits exceeding credits.
+    let debits = [Amount::from_repr(15_000), Amount::from_repr(501)];
+    assert_eq!(Amount::reconcile(&credits, &debits),
+               Err(ReconcileError::InsufficientCredits {
+                   credits: Amount::from_repr(15_500),
+                   debits: Amount::from_repr(15_501),
+               }));
+
+    // Overflows.
+    let huge = [Amount::max_value(), Amount::from_repr(1)];
+    assert_eq!(Amount::reconcile(&huge, &[]), Err(ReconcileError::Overflow));
+    assert_eq!(Amount::reconcile(&credits, &huge), Err(ReconcileError::Overflow));
+}
+
+/// Tricky inputs for the wallet address parser.
+const WALLET_ADDRESS_SEED_CORPUS: &[&str] = &["",
+                                              "f",
+                                              "fr",
+                                              "fr1",
+                                              "r111111111",
+                                              "FR111111111",
+                                              "ƒr111111111",
+                                              "frñ",
+                                              "fr\u{1F600}",
+                                              "ñfr111111111",
+                                              "fr11111111",
+                                              "fr1111111111",
+                                              "fr111111111111111111111111111111",
+                                              "fr 111111111",
+                                              "fr111111111 ",
+                                              "fr111111111\u{0}",
+                                              "fr0OIl",
+                                              "frzzzzzzzzzzzz",
+                                              "fr2222222222",
+                                              "frfrfrfrfrfr"];
+
+/// Checks that parsing the given string does not panic, and that valid addresses round-trip.
+fn check_wallet_address_parse(s: &str) {
+    if let Ok(addr) = WalletAddress::from_str(s) {
+        assert_eq!(WalletAddress::from_str(&format!("{}", addr)).unwrap(), addr);
+    }
+}
+
+#[test]
+fn it_walletaddress_parse_never_panics() {
+    for s in WALLET_ADDRESS_SEED_CORPUS {
+        check_wallet_address_parse(s);
+    }
+
+    let alphabet: Vec<char> = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz0OIlfrñ"
+        .chars()
+        .collect();
+    let mut rng = thread_rng();
+    for _ in 0..2_000 {
+        // Random strings, mostly made of base-58 characters, with or without the prefix.
+        let len = rng.gen_range(0, 20);
+        let mut s: String = (0..len)
+            .map(|_| if rng.gen_weighted_bool(10) {
+                rng.gen::<char>()
+            } else {
+                alphabet[rng.gen_range(0, alphabet.len())]
+            })
+            .collect();
+        if rng.gen() {
+            s.insert_str(0, "fr");
+        }
+        check_wallet_address_parse(&s);
+
+        // Mutations of valid addresses.
+        let mut random_addr = [0u8; WALLET_ADDRESS_LEN];
+        rng.fill_bytes(&mut random_addr[1..]);
+        let mut mutated: Vec<char> = format!("{}", WalletAddress::from_data(random_addr))
+            .chars()
+            .collect();
+        let i = rng.gen_range(0, mutated.len());
+        match rng.gen_range(0, 3) {
+            0 => mutated[i] = alphabet[rng.gen_range(0, alphabet.len())],
+            1 => mutated.truncate(i),
+            _ => mutated.insert(i, rng.gen::<char>()),
+        }
+        check_wallet_address_parse(&mutated.into_iter().collect::<String>());
+    }
+}
+
+#[test]
+fn it_amount_parse_long_decimals() {
+    let amount: Amount = "175.6460000000000001".parse().unwrap();
+    assert_eq!(amount, Amount::from_repr(175_646));
+
+    let amount: Amount = "175.64649999999999999".parse().unwrap();
+    assert_eq!(amount, Amount::from_repr(175_646));
+
+    let amount: Amount = ".9999999999999999999".parse().unwrap();
+    assert_eq!(amount, Amount::from_repr(1_000));
+
+    // Decimals that don't fit in an `u64` are rejected.
+    let amount: Result<Amount, _> = "175.64600000000000000000000001".parse();
+    assert!(amount.unwrap_err().to_string().contains("the decimal part is not a valid u64"));
+    let amount: Result<Amount, _> = ".99999999999999999999999999999".parse();
+    assert!(amount.is_err());
+
+    let amount: Amount = "+175.646".parse().unwrap();
+    assert_eq!(amount, Amount::from_repr(175_646));
+
+    // A sign in the decimal part takes the place of a leading zero.
+    let amount: Amount = "5.+5".parse().unwrap();
+    assert_eq!(amount, Amount::from_repr(5_050));
+    let amount: Amount = "175.+646".parse().unwrap();
+    assert_eq!(amount, Amount::from_repr(175_065));
+
+    let amount: Result<Amount, _> = "5.++5".parse();
+    assert!(amount.is_err());
+    let amount: Result<Amount, _> = "+".parse();
+    assert!(amount.is_err());
+    let amount: Result<Amount, _> = "+.5".parse();
+    assert!(amount.is_err());
+    let amount: Result<Amount, _> = "".parse();
+    assert!(amount.is_err());
+
+    // Units that don't fit in an `u64` are invalid units when there is a decimal part.
+    let amount: Result<Amount, _> = "18446744073709551616.5".parse();
+    assert!(amount.unwrap_err().to_string().contains("the units part it is not a valid u64"));
+    let amount: Result<Amount, _> = "18446744073709551615.5".parse();
+    assert!(amount.unwrap_err().to_string().contains("it is too big"));
+}
+
+#[test]
+fn it_walletaddress_leading_zero_bytes() {
+    let zero = WalletAddress::from_data([0u8; WALLET_ADDRESS_LEN]);
+    assert_eq!(zero.to_string(), "fr111111111");
+    assert_eq!(WalletAddress::from_str("fr111111111").unwrap(), zero);
+    assert!(WalletAddress::from_str("fr1111111111").is_err());
+    assert!(WalletAddress::from_str("fr11111111").is_err());
+
+    for i in 1..WALLET_ADDRESS_LEN {
+        let mut address = [0u8; WALLET_ADDRESS_LEN];
+        address[i] = 0xFF;
+        let address = WalletAddress::from_data(address);
+        assert_eq!(WalletAddress::from_str(&address.to_string()).unwrap(), address);
+    }
+}
+
+#[test]
+fn it_walletaddress_base58_matches_rust_base58() {
+    let mut rng = thread_rng();
+    for i in 0..2_000 {
+        // Some of the payloads start with zero bytes, up to the whole payload.
+        let mut address = [0u8; WALLET_ADDRESS_LEN];
+        rng.fill_bytes(&mut address);
+        for byte in address.iter_mut().take(i % (WALLET_ADDRESS_LEN + 1)) {
+            *byte = 0;
+        }
+        let addr = WalletAddress::from(address);
+        let payload: Vec<u8> = address.iter()
+            .chain(addr.checksum_bytes().iter())
+            .cloned()
+            .collect();
+
+        let encoded = payload.to_base58();
+        assert_eq!(addr.to_string(), format!("fr{}", encoded));
+        assert_eq!(encoded.from_base58().unwrap(), payload);
+        if address[0] == 0x00 {
+            assert_eq!(WalletAddress::from_body_str(&encoded).unwrap(), addr);
+        }
+
+        // Random payloads don't have a valid checksum, but they must decode to the same bytes.
+        let mut random = [0u8; WALLET_ADDRESS_LEN + 2];
+        rng.fill_bytes(&mut random);
+        for byte in random.iter_mut().take(i % (WALLET_ADDRESS_LEN + 3)) {
+            *byte = 0;
+        }
+        let encoded = random.to_base58();
+        assert_eq!(encoded.from_base58().unwrap(), &random[..]);
+        let value = random.iter().fold(0u128, |value, byte| value << 8 | *byte as u128);
+        assert_eq!(WalletAddress::from_body_str(&encoded).ok(),
+                   WalletAddress::from_u128(value).ok());
+    }
+
+    // Both report the same invalid character.
+    for s in &["0", "11O11", "abcIl1", "1111111111l", "frñ"] {
+        let expected = s.from_base58().unwrap_err();
+        let error = WalletAddress::from_body_str(s).unwrap_err();
+        assert!(format!("{:?}", error).contains(&format!("Some({:?})", expected)));
+    }
+}
+
+#[test]
+fn it_fractal_error_from() {
+    let amount_error = "175.6.4".parse::<Amount>().unwrap_err();
+    let message = amount_error.to_string();
+    match FractalError::from(amount_error) {
+        e @ FractalError::AmountParse(_) => {
+            assert_eq!(e.to_string(), message);
+            assert!(e.cause().is_some());
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+
+    let reconcile_error = ReconcileError::Overflow;
+    match FractalError::from(reconcile_error) {
+        FractalError::Reconcile(e) => assert_eq!(e, reconcile_error),
+        e => panic!("unexpected error: {:?}", e),
+    }
+
+    let wallet_address_error = "fr0".parse::<WalletAddress>().unwrap_err();
+    let message = wallet_address_error.to_string();
+    match FractalError::from(wallet_address_error) {
+        e @ FractalError::WalletAddressParse(_) => assert_eq!(e.to_string(), message),
+        e => panic!("unexpected error: {:?}", e),
+    }
+
+    let address_error = Address::parse_single_line("a, b").unwrap_err();
+    match FractalError::from(address_error) {
+        FractalError::Address(e) => assert_eq!(e, address_error),
+        e => panic!("unexpected error: {:?}", e),
+    }
+
+    fn parse_both(amount: &str, address: &str) -> Result<(Amount, WalletAddress), FractalError> {
+        Ok((amount.parse()?, address.parse()?))
+    }
+    match parse_both("175", "fr0") {
+        Err(FractalError::WalletAddressParse(_)) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+fn it_walletaddress_cmp_display() {
+    let mut addresses: Vec<WalletAddress> = [[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF],
+                                             [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01],
+                                             [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10]]
+        .iter()
+        .map(|a| WalletAddress::from_data(*a))
+        .collect();
+
+    addresses.sort();
+    assert_eq!(addresses[0].get_raw()[WALLET_ADDRESS_LEN - 1], 0x01);
+
+    addresses.sort_by(|a, b| a.cmp_display(b));
+    let strings: Vec<String> = addresses.iter().map(|a| a.to_string()).collect();
+    let mut sorted_strings = strings.clone();
+    sorted_strings.sort();
+    assert_eq!(strings, sorted_strings);
+    assert_eq!(addresses[0].get_raw()[WALLET_ADDRESS_LEN - 1], 0xFF);
+
+    for _ in 0..50 {
+        let mut a = [0u8; WALLET_ADDRESS_LEN];
+        let mut b = [0u8; WALLET_ADDRESS_LEN];
+        thread_rng().fill_bytes(&mut a[2..]);
+        thread_rng().fill_bytes(&mut b[3..]);
+        let (a, b) = (WalletAddress::from_data(a), WalletAddress::from_data(b));
+        assert_eq!(a.cmp_display(&b), a.to_string().cmp(&b.to_string()));
+    }
+}
+
+#[test]
+fn it_amount_to_cents_string() {
+    assert_eq!(Amount::from_repr(175_644).to_cents_string(RoundingMode::HalfUp), "17564");
+    assert_eq!(Amount::from_repr(175_645).to_cents_string(RoundingMode::HalfUp), "17565");
+    assert_eq!(Amount::from_repr(175_645).to_cents_string(RoundingMode::HalfEven), "17564");
+    assert_eq!(Amount::from_repr(175_655).to_cents_string(RoundingMode::HalfEven), "17566");
+    assert_eq!(Amount::from_repr(175_646).to_cents_string(RoundingMode::HalfEven), "17565");
+    assert_eq!(Amount::from_repr(4).to_cents_string(RoundingMode::HalfUp), "0");
+    assert_eq!(Amount::min_value().to_cents_string(RoundingMode::HalfUp), "0");
+    assert_eq!(Amount::max_value().to_cents_string(RoundingMode::HalfUp),
+               "1844674407370955162");
+    assert_eq!(Amount::max_value().to_minor_units(RoundingMode::HalfUp),
+               u64::MAX / 10 + 1);
+}
+
+#[test]
+fn it_address_hash_set_dedup() {
+    let mut addresses = HashSet::new();
+    assert!(addresses.insert(Address::new("Unter den Linden 1",
+                                          None,
+                                          "Berlin",
+                                          "Berlin",
+                                          "10117",
+                                          "DE")));
+    assert!(!addresses.insert(Address::parse_single_line("Unter den Linden 1, , Berlin, Berlin, \
+                                                          10117, DE")
+                                  .unwrap()));
+    assert!(addresses.insert(Address::new("Unter den Linden 1",
+                                          Some("Apt. 2"),
+                                          "Berlin",
+                                          "Berlin",
+                                          "10117",
+                                          "DE")));
+    assert_eq!(addresses.len(), 2);
+}
+
+#[test]
+fn it_amount_from_str_with_scale() {
+    assert_eq!(Amount::from_str_with_scale("175", 0).unwrap(),
+               Amount::from_repr(175_000));
+    assert_eq!(Amount::from_str_with_scale("17565", 2).unwrap(),
+               Amount::from_repr(175_650));
+    assert_eq!(Amount::from_str_with_scale("175646", 3).unwrap(),
+               Amount::from_repr(175_646));
+
+    for scale in &[0, 2, 3] {
+        assert_eq!(Amount::from_str_with_scale("175.646", *scale).unwrap(),
+                   Amount::from_repr(175_646));
+        assert!(Amount::from_str_with_scale("", *scale).is_err());
+        assert!(Amount::from_str_with_scale("17a", *scale).is_err());
+    }
+    assert!(Amount::from_str_with_scale("18446744073709552", 0).is_err());
+}
+
+#[test]
+fn it_walletaddress_from_str_with_version() {
+    let mainnet = WalletAddress::from_data([0x00, 0x11, 0x2A, 0x44, 0xCD, 0xFF, 0xE0]);
+    let testnet = WalletAddress::from([0x01, 0x11, 0x2A, 0x44, 0xCD, 0xFF, 0xE0]);
+    assert_eq!(mainnet.get_version(), 0x00);
+    assert_eq!(testnet.get_version(), 0x01);
+
+    let testnet_str = testnet.to_string();
+    let parsed = WalletAddress::from_str_with_version(&testnet_str, &[0x00, 0x01]).unwrap();
+    assert_eq!(parsed, testnet);
+    assert_eq!(WalletAddress::from_str_with_version(&mainnet.to_string(), &[0x00, 0x01]).unwrap(),
+               mainnet);
+
+    let error = WalletAddress::from_str_with_version(&testnet_str, &[0x00, 0x02]).unwrap_err();
+    assert!(error.to_string().contains("0x01"));
+    assert!(WalletAddress::from_str_with_version(&mainnet.to_string(), &[0x01]).is_err());
+    assert!(WalletAddress::from_str_with_version(&mainnet.to_string(), &[]).is_err());
+    assert!(WalletAddress::from_str(&testnet_str).is_err());
+}
+
+#[test]
+fn it_amount_macro() {
+    assert_eq!(amount!("175.646"), Amount::from_repr(175_646));
+    assert_eq!(amount!("0.5"), Amount::from_repr(500));
+    assert_eq!(amount!(repr: 175_646), Amount::from_repr(175_646));
+    assert_eq!(amount!(repr: u64::MAX), Amount::max_value());
+    assert_eq!(amount!("175.646") + amount!(repr: 354), amount!("176"));
+}
+
+#[test]
+#[should_panic(expected = "invalid amount literal \"175,64\"")]
+fn it_amount_macro_invalid_literal() {
+    let _ = amount!("175,64");
+}
+
+#[test]
+fn it_wallet_macro() {
+    assert_eq!(wallet!("fr111111111"),
+               WalletAddress::from_data([0u8; WALLET_ADDRESS_LEN]));
+    let address = WalletAddress::from_data([0x00, 0x11, 0x2A, 0x44, 0xCD, 0xFF, 0xE0]);
+    assert_eq!(wallet!(&address.to_string()), address);
+}
+
+#[test]
+#[should_panic(expected = "checksum fail")]
+fn it_wallet_macro_bad_checksum() {
+    let _ = wallet!("fr111111112");
+}
+
+#[test]
+fn it_amount_checked_running_sum() {
+    assert_eq!(Amount::checked_running_sum(&[]), Ok(Amount::min_value()));
+
+    let amounts = [Amount::from_repr(u64::MAX / 2),
+                   Amount::from_repr(u64::MAX / 2),
+                   Amount::from_repr(1),
+                   Amount::from_repr(1),
+                   Amount::from_repr(1)];
+    assert_eq!(Amount::checked_running_sum(&amounts[..3]), Ok(Amount::max_value()));
+    assert_eq!(Amount::checked_running_sum(&amounts), Err(3));
+}
+
+#[test]
+fn it_amount_inherent_parse() {
+    assert_eq!(Amount::parse("175.646").unwrap(), Amount::from_repr(175_646));
+    assert_eq!(Amount::parse("175.646").unwrap(),
+               Amount::from_str("175.646").unwrap());
+    assert_eq!(Amount::parse("175.6.46").unwrap_err().to_string(),
+               Amount::from_str("175.6.46").unwrap_err().to_string());
+}
+
+#[test]
+fn it_inherent_parse() {
+    assert_eq!(WalletAddress::parse("fr111111111").unwrap(),
+               WalletAddress::from_data([0u8; WALLET_ADDRESS_LEN]));
+    assert!(WalletAddress::parse("fr111111112").is_err());
+
+    let line = "123 Main St, Apt 4, Springfield, IL, 62704, US";
+    assert_eq!(Address::parse(line).unwrap(),
+               Address::parse_single_line(line).unwrap());
+    assert_eq!(Address::parse("123 Main St, Springfield"),
+               Err(AddressError::InvalidFieldCount { found: 2 }));
+}
+
+#[test]
+fn it_amount_display_grouped() {
+    let amount = Amount::from_repr(1_234_560);
+    assert_eq!(format!("{}", amount.display_grouped()), "1,234.56");
+    assert_eq!(format!("{:+}", amount.display_grouped()), "+1,234.56");
+    assert_eq!(format!("{:12}", amount.display_grouped()), "    1,234.56");
+    assert_eq!(format!("{:.1}", amount.display_grouped()), "1,234.6");
+
+    assert_eq!(format!("{:+12.2}", amount.display_grouped()), "   +1,234.56");
+    assert_eq!(format!("{:<+12.2}", amount.display_grouped()), "+1,234.56   ");
+    assert_eq!(format!("{:*^+12.0}", amount.display_grouped()), "***+1,235***");
+    assert_eq!(format!("{:+012.4}", amount.display_grouped()), "+01,234.5600");
+    assert_eq!(format!("{:+4.2}", amount.display_grouped()), "+1,234.56");
+
+    assert_eq!(format!("{:+.2}", Amount::from_repr(999_995).display_grouped()), "+1,000.00");
+    assert_eq!(format!("{:+5}", Amount::min_value().display_grouped()), "   +0");
+    assert_eq!(format!("{}", Amount::max_value().display_grouped()),
+               "18,446,744,073,709,551.615");
+}
+
+#[test]
+fn it_amount_str_has_excess_precision() {
+    assert!(Amount::str_has_excess_precision("175.6465"));
+    assert!(Amount::str_has_excess_precision(".0001"));
+    assert!(!Amount::str_has_excess_precision("175.64"));
+    assert!(!Amount::str_has_excess_precision("175.646"));
+    assert!(!Amount::str_has_excess_precision("175.646000"));
+    assert!(!Amount::str_has_excess_precision("175"));
+}
+
+#[test]
+fn it_walletaddress_network_name() {
+    assert_eq!(WalletAddress::from_data([0u8; WALLET_ADDRESS_LEN]).network_name(),
+               Some("mainnet"));
+    assert_eq!(wallet!("fr111111111").network_name(), Some("mainnet"));
+    assert_eq!(WalletAddress::from([0xFFu8; WALLET_ADDRESS_LEN]).network_name(), None);
+}
+
+#[test]
+fn it_amount_read_write() {
+    let amounts = [Amount::min_value(),
+                   Amount::from_repr(175_646),
+                   Amount::from_repr(0x0102_0304_0506_0708),
+                   Amount::max_value()];
+    let mut buffer = Vec::new();
+    for amount in &amounts {
+        amount.write_to(&mut buffer).unwrap();
+    }
+    assert_eq!(buffer.len(), 8 * amounts.len());
+    assert_eq!(&buffer[16..24], &[1, 2, 3, 4, 5, 6, 7, 8]);
+
+    let mut reader = Cursor::new(buffer);
+    for amount in &amounts {
+        assert_eq!(Amount::read_from(&mut reader).unwrap(), *amount);
+    }
+    assert!(Amount::read_from(&mut reader).is_err());
+
+    let mut short = Cursor::new(vec![0u8; 7]);
+    assert!(Amount::read_from(&mut short).is_err());
+}
+
+#[test]
+fn it_address_redacted() {
+    let address = Address::new("123 Main St",
+                               Some("Apt 4"),
+                               "Springfield",
+                               "IL",
+                               "62704",
+                               "US");
+    let redacted = address.redacted();
+    assert!(!redacted.get_address1().contains("Main"));
+    assert!(!redacted.get_address2().unwrap().contains("4"));
+    assert!(!redacted.get_zip().contains("62704"));
+    assert_eq!(redacted.get_city(), "Springfield");
+    assert_eq!(redacted.get_state(), "IL");
+    assert_eq!(redacted.get_country(), "US");
+
+    let address = Address::new("123 Main St", None, "Springfield", "IL", "62704", "US");
+    assert_eq!(address.redacted().get_address2(), None);
+}
+
+#[test]
+fn it_amount_to_iso_minor_units() {
+    let amount = Amount::from_repr(2_500); // 2.5
+    assert_eq!(amount.to_iso_minor_units(0, RoundingMode::HalfUp), 3);
+    assert_eq!(amount.to_iso_minor_units(0, RoundingMode::HalfEven), 2);
+    assert_eq!(Amount::from_repr(2_499).to_iso_minor_units(0, RoundingMode::HalfUp), 2);
+
+    let amount = Amount::from_repr(1_625); // 1.625
+    assert_eq!(amount.to_iso_minor_units(2, RoundingMode::HalfUp), 163);
+    assert_eq!(amount.to_iso_minor_units(2, RoundingMode::HalfEven), 162);
+    assert_eq!(amount.to_iso_minor_units(2, RoundingMode::HalfUp),
+               amount.to_minor_units(RoundingMode::HalfUp));
+
+    assert_eq!(amount.to_iso_minor_units(3, RoundingMode::HalfUp), 1_625);
+    assert_eq!(amount.to_iso_minor_units(3, RoundingMode::HalfEven), 1_625);
+    assert_eq!(Amount::max_value().to_iso_minor_units(3, RoundingMode::HalfUp), u64::MAX);
+    assert_eq!(Amount::max_value().to_iso_minor_units(0, RoundingMode::HalfUp),
+               18_446_744_073_709_552);
+}
+
+#[test]
+fn it_walletaddress_eq_raw() {
+    let raw = [0x00, 0x11, 0x2A, 0x44, 0xCD, 0xFF, 0xE0];
+    let address = WalletAddress::from_data(raw);
+    assert!(address == raw);
+    assert_eq!(address, raw);
+    assert!(address != [0x00, 0x11, 0x2A, 0x44, 0xCD, 0xFF, 0xE1]);
+    assert!(address != [0u8; WALLET_ADDRESS_LEN]);
+}
+
+#[test]
+fn it_amount_percent_change_from() {
+    let baseline = Amount::from_repr(80_000);
+    assert_eq!(Amount::from_repr(100_000).percent_change_from(&baseline), Some(25.0));
+    assert_eq!(Amount::from_repr(60_000).percent_change_from(&baseline), Some(-25.0));
+    assert_eq!(Amount::min_value().percent_change_from(&baseline), Some(-100.0));
+    assert_eq!(baseline.percent_change_from(&baseline), Some(0.0));
+    assert_eq!(baseline.percent_change_from(&Amount::min_value()), None);
+    assert_eq!(Amount::min_value().percent_change_from(&Amount::min_value()), None);
+}
+
+#[test]
+fn it_address_us_state() {
+    assert_eq!("CA".parse::<UsState>(), Ok(UsState::California));
+    assert_eq!("ca".parse::<UsState>(), Ok(UsState::California));
+    assert_eq!("California".parse::<UsState>(), Ok(UsState::California));
+    assert_eq!(" district of columbia ".parse::<UsState>(),
+               Ok(UsState::DistrictOfColumbia));
+    assert_eq!("Cal".parse::<UsState>(), Err(AddressError::InvalidState));
+    assert_eq!("".parse::<UsState>(), Err(AddressError::InvalidState));
+    assert_eq!(UsState::California.to_string(), "CA");
+    assert_eq!(UsState::California.get_name(), "California");
+
+    let address = Address::new("1 Infinite Loop", None, "Cupertino", "CA", "95014", "us");
+    assert_eq!(address.parse_us_state(), Some(Ok(UsState::California)));
+
+    let address = Address::new("1 Infinite Loop", None, "Cupertino", "Calif.", "95014", "US");
+    assert_eq!(address.parse_us_state(), Some(Err(AddressError::InvalidState)));
+    assert_eq!(address.get_state(), "Calif.");
+
+    let address = Address::new("Gran Vía 1", None, "Madrid", "CA", "28013", "ES");
+    assert_eq!(address.parse_us_state(), None);
+}
+
+#[test]
+fn it_amount_allocate_capped() {
+    let caps = [Amount::from_repr(50_000), Amount::from_repr(30_000), Amount::from_repr(20_000)];
+
+    let (allocations, remainder) = Amount::from_repr(60_000).allocate_capped(&caps);
+    assert_eq!(allocations,
+               vec![Amount::from_repr(50_000), Amount::from_repr(10_000), Amount::min_value()]);
+    assert_eq!(remainder, Amount::min_value());
+
+    let (allocations, remainder) = Amount::from_repr(125_500).allocate_capped(&caps);
+    assert_eq!(allocations, caps.to_vec());
+    assert_eq!(remainder, Amount::from_repr(25_500));
+
+    let (allocations, remainder) = Amount::from_repr(10_000).allocate_capped(&[]);
+    assert!(allocations.is_empty());
+    assert_eq!(remainder, Amount::from_repr(10_000));
+}
+
+#[test]
+fn it_walletaddress_identicon_seed() {
+    let address = WalletAddress::from_data([0u8; WALLET_ADDRESS_LEN]);
+    assert_eq!(address.identicon_seed(), 0x462A_7C27);
+    assert_eq!(address.identicon_seed(), wallet!("fr111111111").identicon_seed());
+
+    let other = WalletAddress::from_data([0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01]);
+    assert_eq!(other.identicon_seed(), 0x452A_7A94);
+    assert!(address.identicon_seed() != other.identicon_seed());
+}
+
+#[test]
+fn it_amount_from_str_lenient() {
+    assert!(Amount::from_str(".").is_err());
+    assert_eq!(Amount::from_str_lenient(".").unwrap(), Amount::min_value());
+
+    assert_eq!(Amount::from_str_lenient("175.646").unwrap(), Amount::from_repr(175_646));
+    assert_eq!(Amount::from_str_lenient(".5").unwrap(), Amount::from_repr(500));
+    assert!(Amount::from_str_lenient("..").is_err());
+    assert!(Amount::from_str_lenient("").is_err());
+}
+
+#[test]
+fn it_amount_sort_descending() {
+    let mut amounts = [Amount::from_repr(5_000),
+                       Amount::max_value(),
+                       Amount::min_value(),
+                       Amount::from_repr(175_646),
+                       Amount::from_repr(5_000)];
+    Amount::sort_descending(&mut amounts);
+    assert_eq!(amounts[0], Amount::max_value());
+    assert_eq!(amounts,
+               [Amount::max_value(),
+                Amount::from_repr(175_646),
+                Amount::from_repr(5_000),
+                Amount::from_repr(5_000),
+                Amount::min_value()]);
+
+    assert_eq!(Amount::from_repr(1).cmp_desc(&Amount::from_repr(2)), Ordering::Greater);
+    assert_eq!(Amount::from_repr(2).cmp_desc(&Amount::from_repr(2)), Ordering::Equal);
+}
+
+#[test]
+fn it_address_validate_required_fields() {
+    let address = Address::new("123 Main St", None, "Springfield", "IL", "62704", "US");
+    assert_eq!(address.validate_required_fields(), Ok(()));
+
+    let address = Address::new("123 Main St", None, "Springfield", " ", "62704", "us");
+    assert_eq!(address.validate_required_fields(),
+               Err(AddressError::MissingField { field: AddressField::State }));
+
+    let address = Address::new("Unter den Linden 1", None, "Berlin", "", "10117", "DE");
+    assert_eq!(address.validate_required_fields(), Ok(()));
+
+    let address = Address::new("Unter den Linden 1", None, "", "", "10117", "DE");
+    assert_eq!(address.validate_required_fields(),
+               Err(AddressError::MissingField { field: AddressField::City }));
+
+    let address = Address::new("Unter den Linden 1", None, "Berlin", "Berlin", "10117", "");
+    assert_eq!(address.validate_required_fields(),
+               Err(AddressError::MissingField { field: AddressField::Country }));
+}
+
+#[test]
+fn it_amount_format_min_int_digits() {
+    assert_eq!(Amount::from_repr(5_500).format_min_int_digits(2), "05.5");
+    assert_eq!(Amount::from_repr(5_000).format_min_int_digits(3), "005");
+    assert_eq!(Amount::from_repr(500).format_min_int_digits(2), "00.5");
+    assert_eq!(Amount::from_repr(175_646).format_min_int_digits(2), "175.646");
+    assert_eq!(Amount::from_repr(17_646).format_min_int_digits(2), "17.646");
+    assert_eq!(Amount::from_repr(5_500).format_min_int_digits(0), "5.5");
+}
+
+#[cfg(feature = "test-vectors")]
+#[test]
+fn it_walletaddress_test_vectors() {
+    for (raw, expected) in fractal_utils::wallet_address::test_vectors() {
+        let address = WalletAddress::from_data(raw);
+        assert_eq!(address.to_string(), expected);
+        assert_eq!(expected.parse::<WalletAddress>().unwrap(), address);
+    }
+}
+
+#[cfg(feature = "test-vectors")]
+#[test]
+fn it_amount_test_vectors() {
+    for (input, repr, display) in fractal_utils::amount::test_vectors() {
+        let amount = input.parse::<Amount>().unwrap();
+        assert_eq!(amount, Amount::from_repr(repr), "parsing {:?}", input);
+        assert_eq!(amount.to_string(), display);
+        assert_eq!(display.parse::<Amount>().unwrap(), amount);
+    }
+}
+
+#[test]
+fn it_amount_div_tracked() {
+    assert_eq!(Amount::from_repr(175_646).div_tracked(2),
+               LossyAmount {
+                   amount: Amount::from_repr(87_823),
+                   lossy: false,
+               });
+    assert_eq!(Amount::from_repr(175_646).div_tracked(4),
+               LossyAmount {
+                   amount: Amount::from_repr(43_911),
+                   lossy: true,
+               });
+    assert_eq!(Amount::from_repr(175_646).div_tracked(4).amount,
+               Amount::from_repr(175_646) / 4u64);
+    assert!(!Amount::min_value().div_tracked(7).lossy);
+}
+
+#[test]
+fn it_walletaddress_u128() {
+    for _ in 0..50 {
+        let mut random_addr = [0u8; WALLET_ADDRESS_LEN];
+        thread_rng().fill_bytes(&mut random_addr[1..]);
+        let address = WalletAddress::from_data(random_addr);
+        assert_eq!(WalletAddress::from_u128(address.to_u128()).unwrap(), address);
+        assert!(address.to_u128() >> ((WALLET_ADDRESS_LEN + 2) * 8) == 0);
+    }
+
+    let address = WalletAddress::from_data([0x00, 0x11, 0x2A, 0x44, 0xCD, 0xFF, 0xE0]);
+    let packed = address.to_u128();
+    assert_eq!(packed & 0xFFFF, 0xAD07);
+    let error = WalletAddress::from_u128(packed ^ (1 << 20)).unwrap_err();
+    assert!(error.to_string().contains("checksum"));
+    assert!(WalletAddress::from_u128(packed ^ 1).is_err());
+    assert!(WalletAddress::from_u128(packed | (1 << 64)).is_err());
+    assert!(WalletAddress::from_u128(packed | (1 << 72)).is_err());
+}
+
+#[test]
+fn it_amount_zero_format() {
+    let zero = Amount::min_value();
+    assert_eq!(format!("{}", zero), "0");
+    assert_eq!(format!("{:.0}", zero), "0");
+    assert_eq!(format!("{:.1}", zero), "0.0");
+    assert_eq!(format!("{:.2}", zero), "0.00");
+    assert_eq!(format!("{:.3}", zero), "0.000");
+    assert_eq!(format!("{:.5}", zero), "0.00000");
+
+    assert_eq!(format!("{:1}", zero), "0");
+    assert_eq!(format!("{:5}", zero), "00000");
+    assert_eq!(format!("{:05}", zero), "00000");
+    assert_eq!(format!("{:05.0}", zero), "00000");
+    assert_eq!(format!("{:05.1}", zero), "000.0");
+    assert_eq!(format!("{:05.2}", zero), "00.00");
+    assert_eq!(format!("{:05.3}", zero), "0.000");
+    assert_eq!(format!("{:05.4}", zero), "0.0000");
+    assert_eq!(format!("{:08.5}", zero), "00.00000");
+
+    // Amounts smaller than the precision are rounded to zero.
+    assert_eq!(format!("{:05.0}", Amount::from_repr(499)), "00000");
+    assert_eq!(format!("{:05.1}", Amount::from_repr(49)), "000.0");
+    assert_eq!(format!("{:05.2}", Amount::from_repr(4)), "00.00");
+    assert_eq!(format!("{:.0}", Amount::from_repr(500)), "1");
+
+    assert_eq!(format!("{:.2}", zero.display_rounded(RoundingMode::HalfEven)), "0.00");
+    assert_eq!(format!("{:05.0}", Amount::from_repr(500).display_rounded(RoundingMode::HalfEven)),
+               "00000");
+}
+
+#[test]
+fn it_amount_rounding_mode_zero_directions() {
+    let modes = [RoundingMode::HalfUp,
+                 RoundingMode::HalfEven,
+                 RoundingMode::HalfAwayFromZero,
+                 RoundingMode::HalfTowardZero];
+    let round = |repr: u64, mode: RoundingMode| {
+        format!("{:.0}", Amount::from_repr(repr).display_rounded(mode))
+    };
+
+    let midpoints: Vec<String> = modes.iter().map(|m| round(2_500, *m)).collect();
+    assert_eq!(midpoints, ["3", "2", "3", "2"]);
+    let midpoints: Vec<String> = modes.iter().map(|m| round(3_500, *m)).collect();
+    assert_eq!(midpoints, ["4", "4", "4", "3"]);
+
+    for mode in &modes {
+        assert_eq!(round(2_499, *mode), "2");
+        assert_eq!(round(2_501, *mode), "3");
+    }
+
+    let amount = Amount::from_repr(1_625);
+    assert_eq!(amount.to_minor_units(RoundingMode::HalfAwayFromZero), 163);
+    assert_eq!(amount.to_minor_units(RoundingMode::HalfTowardZero), 162);
+    assert_eq!(Amount::from_repr(1_626).to_minor_units(RoundingMode::HalfTowardZero), 163);
+}
+
+#[test]
+fn it_address_matches_query() {
+    let address = Address::new("123 Main St", Some("Apt 4"), "Springfield", "IL", "62704", "US");
+    let other = Address::new("742 Evergreen Terrace", None, "Springfield", "OR", "97403", "US");
+    let query = AddressQuery {
+        city: Some("SPRINGFIELD".to_owned()),
+        zip: Some("62704".to_owned()),
+        ..Default::default()
+    };
+    assert!(address.matches_query(&query));
+    assert!(!other.matches_query(&query));
+
+    assert!(address.matches_query(&AddressQuery::default()));
+    assert!(other.matches_query(&AddressQuery::default()));
+
+    let query = AddressQuery { address2: Some("apt".to_owned()), ..Default::default() };
+    assert!(address.matches_query(&query));
+    assert!(!other.matches_query(&query));
+}
+
+#[test]
+fn it_amount_format_large_precision() {
+    let amount = Amount::from_repr(175_646);
+    let formatted = format!("{:.60000}", amount);
+    assert_eq!(formatted.len(), "175.".len() + 60_000);
+    assert!(formatted.starts_with("175.646000"));
+    assert!(formatted[7..].bytes().all(|b| b == b'0'));
+
+    let formatted = format!("{:60010.60000}", amount);
+    assert_eq!(formatted.len(), 60_010);
+    assert!(formatted.starts_with("000000175.646"));
+
+    let formatted = format!("{:.60000}", amount.display_grouped());
+    assert_eq!(formatted.len(), "175.".len() + 60_000);
+}
+
+#[test]
+fn it_walletaddress_is_canonical_string() {
+    for _ in 0..50 {
+        let mut random_addr = [0u8; WALLET_ADDRESS_LEN];
+        thread_rng().fill_bytes(&mut random_addr[1..]);
+        let address = WalletAddress::from_data(random_addr);
+        assert!(WalletAddress::is_canonical_string(&address.to_string()));
+
+        let fletcher = address.to_string_with_scheme(ChecksumScheme::Fletcher);
+        if fletcher != address.to_string() {
+            assert!(fletcher.parse::<WalletAddress>().is_ok());
+            assert!(!WalletAddress::is_canonical_string(&fletcher));
+        }
+    }
+
+    assert!(WalletAddress::is_canonical_string("fr111111111"));
+    assert!(!WalletAddress::is_canonical_string("fr1111111111"));
+    assert!(!WalletAddress::is_canonical_string("fr111111112"));
+    assert!(!WalletAddress::is_canonical_string(" fr111111111"));
+    assert!(!WalletAddress::is_canonical_string(""));
+}
+
+#[test]
+fn it_amount_to_words() {
+    assert_eq!(Amount::from_repr(175_646).to_words(),
+               "one hundred seventy-five and 646/1000");
+    assert_eq!(Amount::from_repr(0).to_words(), "zero and 000/1000");
+    assert_eq!(Amount::from_repr(5).to_words(), "zero and 005/1000");
+    assert_eq!(Amount::from_repr(13_000).to_words(), "thirteen and 000/1000");
+    assert_eq!(Amount::from_repr(40_500).to_words(), "forty and 500/1000");
+    assert_eq!(Amount::from_repr(1_000_000_000).to_words(), "one million and 000/1000");
+    assert_eq!(Amount::from_repr(2_001_010_000).to_words(),
+               "two million one thousand ten and 000/1000");
+    assert_eq!(Amount::max_value().to_words(),
+               "eighteen quadrillion four hundred forty-six trillion seven hundred forty-four \
+                billion seventy-three million seven hundred nine thousand five hundred \
+                fifty-one and 615/1000");
+}
+
+#[test]
+fn it_amount_from_words() {
+    for &repr in &[0, 5, 175_646, 13_000, 40_500, 2_001_010_000, u64::MAX] {
+        let amount = Amount::from_repr(repr);
+        assert_eq!(Amount::from_words(&amount.to_words()).unwrap(), amount);
+    }
+    assert_eq!(Amount::from_words("seventy five").unwrap(), Amount::from_repr(75_000));
+    assert_eq!(Amount::from_words("zero and 5/1000").unwrap(), Amount::from_repr(5));
+
+    assert!(Amount::from_words("").is_err());
+    assert!(Amount::from_words("flibber jabber").is_err());
+    assert!(Amount::from_words("one hundred and 5/100").is_err());
+    assert!(Amount::from_words("one thousand two million").is_err());
+    assert!(Amount::from_words("one hundred zero").is_err());
+    assert!(Amount::from_words("nineteen quadrillion").is_err());
+
+    // Number words in the wrong order.
+    assert!(Amount::from_words("five five").is_err());
+    assert!(Amount::from_words("twenty twenty").is_err());
+    assert!(Amount::from_words("one two three").is_err());
+    assert!(Amount::from_words("five twenty").is_err());
+    assert!(Amount::from_words("twenty eleven").is_err());
+    assert!(Amount::from_words("eleven five").is_err());
+    assert!(Amount::from_words("twenty hundred").is_err());
+    assert!(Amount::from_words("one hundred hundred").is_err());
+    assert_eq!(Amount::from_words("twenty five").unwrap(), Amount::from_repr(25_000));
+    assert_eq!(Amount::from_words("one hundred eleven").unwrap(), Amount::from_repr(111_000));
+    assert_eq!(Amount::from_words("five thousand five").unwrap(), Amount::from_repr(5_005_000));
+}
+
+#[test]
+fn it_amount_checked_ops() {
+    let amount = Amount::from_repr(175_646);
+    assert_eq!(Amount::max_value().checked_add(Amount::from_repr(1)), None);
+    assert_eq!(Amount::max_value().checked_add(Amount::min_value()),
+               Some(Amount::max_value()));
+    assert_eq!(amount.checked_add(amount), Some(amount + amount));
+
+    assert_eq!(amount.checked_sub(amount), Some(Amount::min_value()));
+    assert_eq!(amount.checked_sub(Amount::from_repr(175_647)), None);
+
+    assert_eq!(amount.checked_mul(3), Some(amount * 3u64));
+    assert_eq!(amount.checked_mul(0), Some(Amount::min_value()));
+    assert_eq!(Amount::max_value().checked_mul(2), None);
+
+    assert_eq!(amount.checked_div(3), Some(amount / 3u64));
+    assert_eq!(amount.checked_div(0), None);
+}
+
+#[test]
+fn it_amount_saturating_ops() {
+    let amount = Amount::from_repr(175_646);
+    assert_eq!(Amount::max_value().saturating_add(Amount::from_repr(5)), Amount::max_value());
+    assert_eq!(amount.saturating_add(amount), amount + amount);
+
+    assert_eq!(amount.saturating_sub(Amount::from_repr(175_647)), Amount::min_value());
+    assert_eq!(Amount::min_value().saturating_sub(amount), Amount::min_value());
+    assert_eq!(amount.saturating_sub(Amount::from_repr(646)), Amount::from_repr(175_000));
+
+    assert_eq!(Amount::max_value().saturating_mul(2), Amount::max_value());
+    assert_eq!(amount.saturating_mul(3), amount * 3u64);
+}
+
+#[test]
+fn it_amount_scale_by_all() {
+    let amount = Amount::from_repr(175_646);
+    assert_eq!(amount.scale_by_all(&[2, 5, 100]), Some(amount * 1_000u64));
+    assert_eq!(amount.scale_by_all(&[1]), Some(amount));
+    assert_eq!(amount.scale_by_all(&[7, 0, 3]), Some(Amount::min_value()));
+
+    assert_eq!(amount.scale_by_all(&[1_000_000, 1_000_000, 1_000]), None);
+    // A zero after an overflow doesn't recover the product.
+    assert_eq!(amount.scale_by_all(&[u64::MAX, 0]), None);
+}
+
+#[test]
+fn it_amount_zero() {
+    assert_eq!(Amount::zero(), Amount::from_repr(0));
+    assert_eq!(Amount::zero(), Amount::min_value());
+    assert!(Amount::zero().is_zero());
+    assert!(Amount::from_repr(0).is_zero());
+    assert!(!Amount::from_repr(1).is_zero());
+    assert!(!Amount::from_repr(175_646).is_zero());
+    assert!(!Amount::max_value().is_zero());
+}
+
+#[test]
+fn it_wallet_address_grouped_string() {
+    let zero = WalletAddress::from_data([0u8; WALLET_ADDRESS_LEN]);
+    assert_eq!(zero.to_grouped_string(4, '-'), "fr-1111-1111-1");
+    assert_eq!(WalletAddress::from_grouped_str("fr-1111-1111-1", '-').unwrap(), zero);
+
+    let addr = WalletAddress::from_data([0x00, 0x11, 0x2A, 0x44, 0xCD, 0xFF, 0xE0]);
+    let addr_str = addr.to_string();
+    for group in 1..addr_str.len() {
+        let grouped = addr.to_grouped_string(group, ' ');
+        assert!(grouped.starts_with("fr "));
+        assert_eq!(grouped.replace(' ', ""), addr_str);
+        assert_eq!(WalletAddress::from_grouped_str(&grouped, ' ').unwrap(), addr);
+    }
+    assert_eq!(addr.to_grouped_string(0, ' '), addr_str);
+    assert_eq!(WalletAddress::from_grouped_str(&addr_str, '-').unwrap(), addr);
+
+    assert!(WalletAddress::from_grouped_str("fr-1111-1111-1", ' ').is_err());
+
+    // The prefix must be written as is, and only the body can have separators.
+    assert!(WalletAddress::from_grouped_str("f-r-1111-1111-1", '-').is_err());
+    assert!(WalletAddress::from_grouped_str("-fr-1111-1111-1", '-').is_err());
+    assert!(WalletAddress::from_grouped_str("1111-1111-1", '-').is_err());
+
+    // Separators that are base-58 characters are rejected, even if they would give an address.
+    assert!(WalletAddress::from_grouped_str("fr111111111", '1').is_err());
+    let error = WalletAddress::from_grouped_str("frx1111x1111x1", 'x').unwrap_err();
+    assert!(error.to_string().contains("the separator 'x' is a base-58 character"));
+    assert!(WalletAddress::from_grouped_str("fr-1111-1111-1", 'f').is_err());
+    // `0` is not a base-58 character, so it can be used.
+    assert_eq!(WalletAddress::from_grouped_str("fr011110111101", '0').unwrap(), zero);
+}
+
+#[test]
+fn it_amount_from_str_strict() {
+    assert!(Amount::from_str_strict("0175").is_err());
+    assert_eq!("0175".parse::<Amount>().unwrap(), Amount::from_repr(175_000));
+    assert_eq!(Amount::from_str_strict("0175").unwrap_err().position(), Some(0));
+    assert_eq!(Amount::from_str_strict("+00.5").unwrap_err().position(), Some(1));
+    assert!(Amount::from_str_strict("00").is_err());
+
+    // Leading zeros are found after the currency symbol and with thousands separators too.
+    assert!(Amount::from_str_strict("₣0175").is_err());
+    assert_eq!(Amount::from_str_strict("₣0175").unwrap_err().position(), Some(3));
+    assert!(Amount::from_str_strict("₣ 0175").is_err());
+    assert_eq!(Amount::from_str_strict("₣ +0175").unwrap_err().position(), Some(5));
+    assert!(Amount::from_str_strict("0,175").is_err());
+    assert_eq!("0,175".parse::<Amount>().unwrap(), Amount::from_repr(175_000));
+    assert_eq!(Amount::from_str_strict("₣ 1,175").unwrap(), Amount::from_repr(1_175_000));
+    assert_eq!(Amount::from_str_strict("₣0.5").unwrap(), Amount::from_repr(500));
+
+    assert_eq!(Amount::from_str_strict("0").unwrap(), Amount::from_repr(0));
+    assert_eq!(Amount::from_str_strict("0.5").unwrap(), Amount::from_repr(500));
+    assert_eq!(Amount::from_str_strict(".5").unwrap(), Amount::from_repr(500));
+    assert_eq!(Amount::from_str_strict("175.050").unwrap(), Amount::from_repr(175_050));
+    assert_eq!(Amount::from_str_strict("+175").unwrap(), Amount::from_repr(175_000));
+    assert!(Amount::from_str_strict("17a").is_err());
+}
+
+#[test]
+fn it_amount_default() {
+    assert_eq!(Amount::default(), Amount::from_repr(0));
+    assert!(Amount::default().is_zero());
+
+    #[derive(Default)]
+    struct Balance {
+        available: Amount,
+        pending: Amount,
+    }
+    let balance = Balance::default();
+    assert_eq!(balance.available + balance.pending, Amount::zero());
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn it_amount_compat_json() {
+    use rustc_serialize::json::Json;
+    use fractal_utils::compat;
+
+    for &repr in &[0, 175_646, u64::MAX] {
+        let amount = Amount::from_repr(repr);
+
+        let rustc = compat::amount_to_rustc_json(&amount);
+        assert_eq!(compat::amount_from_rustc_json(&rustc), Some(amount));
+        let serde = compat::amount_to_serde_json(&amount);
+        assert_eq!(compat::amount_from_serde_json(&serde), Some(amount));
+
+        // Serialized with `rustc_serialize` and read with `serde_json`.
+        let value: serde_json::Value = serde_json::from_str(&rustc.to_string()).unwrap();
+        assert_eq!(compat::amount_from_serde_json(&value), Some(amount));
+        assert_eq!(compat::rustc_json_to_serde_json(&rustc), Some(value));
+
+        // Serialized with `serde_json` and read with `rustc_serialize`.
+        let json = Json::from_str(&serde_json::to_string(&serde).unwrap()).unwrap();
+        assert_eq!(compat::amount_from_rustc_json(&json), Some(amount));
+        assert_eq!(compat::serde_json_to_rustc_json(&serde), Some(json));
+    }
+
+    assert_eq!(compat::amount_from_rustc_json(&Json::String(String::from("175.646"))), None);
+    assert_eq!(compat::amount_from_rustc_json(&Json::I64(-1)), None);
+    assert_eq!(compat::amount_from_serde_json(&serde_json::Value::Null), None);
+}
+
+#[test]
+fn it_walletaddress_ord_stability() {
+    let data = [[0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+                [0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00],
+                [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+                [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF],
+                [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+                [0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+                [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01],
+                [0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01]];
+    let mut addresses: Vec<_> = data.iter().map(|&d| WalletAddress::from(d)).collect();
+    addresses.sort();
+
+    let sorted: Vec<_> = addresses.iter().map(|a| a.get_raw().to_vec()).collect();
+    assert_eq!(sorted,
+               vec![vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+                    vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01],
+                    vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF],
+                    vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00],
+                    vec![0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+                    vec![0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+                    vec![0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01],
+                    vec![0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]]);
+
+    // The order is the same as the one of the raw bytes.
+    let mut raw = data.to_vec();
+    raw.sort();
+    assert!(addresses.iter().zip(&raw).all(|(a, r)| a == r));
+}
+
+#[test]
+fn it_amount_try_from_f64() {
+    assert_eq!(Amount::try_from(175.646f64).unwrap(), Amount::from_repr(175_646));
+    assert_eq!(Amount::try_from(0f64).unwrap(), Amount::zero());
+    assert_eq!(Amount::try_from(-0f64).unwrap(), Amount::zero());
+    assert_eq!(Amount::try_from(0.0005f64).unwrap(), Amount::from_repr(1));
+    assert_eq!(Amount::try_from(0.0004f64).unwrap(), Amount::zero());
+    assert_eq!(Amount::try_from(1e16f64).unwrap(), Amount::from_repr(10_000_000_000_000_000_000));
+
+    assert!(Amount::try_from(-175.646f64).is_err());
+    assert!(Amount::try_from(-0.0001f64).is_err());
+    assert!(Amount::try_from(f64::NAN).is_err());
+    assert!(Amount::try_from(f64::INFINITY).is_err());
+    assert!(Amount::try_from(f64::NEG_INFINITY).is_err());
+    assert!(Amount::try_from(18446744073709551.615f64).is_err());
+}
+
+#[test]
+fn it_amount_to_f64() {
+    assert!((Amount::from_repr(175_646).to_f64() - 175.646).abs() < 1e-9);
+    assert_eq!(Amount::zero().to_f64(), 0.0);
+    assert_eq!(Amount::from_repr(1).to_f64(), 0.001);
+    assert!((Amount::max_value().to_f64() - 18446744073709551.615).abs() < 10.0);
+}
+
+#[test]
+fn it_amount_format_zero_precision_padded() {
+    assert_eq!(format!("{:05.0}", Amount::from_repr(56_000)), "00056");
+    assert_eq!(format!("{:08.0}", Amount::from_repr(999_600)), "00001000");
+    assert_eq!(format!("{:08.0}", Amount::from_repr(999_499)), "00000999");
+    assert_eq!(format!("{:04.0}", Amount::from_repr(9_999_500)), "10000");
+    assert_eq!(format!("{:03.0}", Amount::from_repr(400)), "000");
+    assert_eq!(format!("{:03.0}", Amount::from_repr(500)), "001");
+}
+
+#[test]
+fn it_address_matches_ignoring_line2() {
+    let address = Address::new("123 Main St", Some("Apt 4"), "Springfield", "IL", "62704", "US");
+    let no_line2 = Address::new("123 Main St", None, "Springfield", "IL", "62704", "US");
+    let other_line2 = Address::new("123 Main St", Some("Apt 7"), "Springfield", "IL", "62704",
+                                   "US");
+    assert!(address.matches_ignoring_line2(&no_line2));
+    assert!(address.matches_ignoring_line2(&other_line2));
+    assert!(no_line2.matches_ignoring_line2(&address));
+    assert!(address.matches_ignoring_line2(&address));
+
+    let other_city = Address::new("123 Main St", Some("Apt 4"), "Shelbyville", "IL", "62704",
+                                  "US");
+    assert!(!address.matches_ignoring_line2(&other_city));
+    let other_zip = Address::new("123 Main St", None, "Springfield", "IL", "62705", "US");
+    assert!(!address.matches_ignoring_line2(&other_zip));
+}
+
+#[test]
+fn it_amount_from_units() {
+    assert_eq!(Amount::from_units(175), Some(Amount::from_repr(175_000)));
+    assert_eq!(Amount::from_units(0), Some(Amount::zero()));
+    assert_eq!(Amount::from_units(u64::MAX / 1_000),
+               Some(Amount::from_repr(u64::MAX / 1_000 * 1_000)));
+    assert_eq!(Amount::from_units(u64::MAX / 1_000 + 1), None);
+
+    assert_eq!(Amount::from_units_decimals(175, 646), Some(Amount::from_repr(175_646)));
+    assert_eq!(Amount::from_units_decimals(0, 999), Some(Amount::from_repr(999)));
+    assert_eq!(Amount::from_units_decimals(u64::MAX / 1_000, 615), Some(Amount::max_value()));
+    assert_eq!(Amount::from_units_decimals(u64::MAX / 1_000, 616), None);
+    assert_eq!(Amount::from_units_decimals(175, 1_000), None);
+    assert_eq!(Amount::from_units_decimals(0, u16::max_value()), None);
+}
+
+#[test]
+fn it_amount_div_rounded() {
+    let amount = Amount::from_repr(10_000);
+    assert_eq!(amount / 3u64, Amount::from_repr(3_333));
+    assert_eq!(amount.div_rounded(3, RoundingMode::HalfUp), Some(Amount::from_repr(3_333)));
+    assert_eq!(Amount::from_repr(20_000).div_rounded(3, RoundingMode::HalfUp),
+               Some(Amount::from_repr(6_667)));
+    assert_eq!(amount.div_rounded(0, RoundingMode::HalfUp), None);
+    assert_eq!(amount.div_rounded(1, RoundingMode::HalfUp), Some(amount));
+
+    // 0.005 / 2 is halfway between 0.002 and 0.003.
+    let half = Amount::from_repr(5);
+    assert_eq!(half.div_rounded(2, RoundingMode::HalfUp), Some(Amount::from_repr(3)));
+    assert_eq!(half.div_rounded(2, RoundingMode::HalfEven), Some(Amount::from_repr(2)));
+    assert_eq!(half.div_rounded(2, RoundingMode::HalfTowardZero), Some(Amount::from_repr(2)));
+    assert_eq!(Amount::max_value().div_rounded(2, RoundingMode::HalfUp),
+               Some(Amount::from_repr(u64::MAX / 2 + 1)));
+}
+
+#[test]
+fn it_amount_units_millis() {
+    let amount = Amount::from_repr(175_646);
+    assert_eq!(amount.units(), 175);
+    assert_eq!(amount.millis(), 646);
+    assert_eq!(Amount::from_units_decimals(amount.units(), amount.millis()), Some(amount));
+
+    assert_eq!(Amount::zero().units(), 0);
+    assert_eq!(Amount::zero().millis(), 0);
+    assert_eq!(Amount::from_repr(999).units(), 0);
+    assert_eq!(Amount::from_repr(999).millis(), 999);
+    assert_eq!(Amount::max_value().units(), 18_446_744_073_709_551);
+    assert_eq!(Amount::max_value().millis(), 615);
+}
+
+#[test]
+fn it_walletaddress_ambiguous_chars() {
+    let error = "fr11111O111".parse::<WalletAddress>().unwrap_err();
+    assert_eq!(error.description(),
+               "the wallet address \"fr11111O111\" is not a valid Fractal Global wallet address, \
+                the character 'O' at position 7 is not used in wallet addresses, since it can be \
+                confused with other characters, it might be an 'o'");
+
+    let error = "fr0".parse::<WalletAddress>().unwrap_err();
+    assert!(error.description().contains("the character '0' at position 2"));
+    assert!(error.description().ends_with("it might be an 'o'"));
+
+    let error = "frl11111111".parse::<WalletAddress>().unwrap_err();
+    assert!(error.description().ends_with("it might be a '1'"));
+    let error = "fr1111I1111".parse::<WalletAddress>().unwrap_err();
+    assert!(error.description().ends_with("it might be a '1'"));
+
+    let error = "fr1111#1111".parse::<WalletAddress>().unwrap_err();
+    assert!(error.description().contains("not a valid base-58 encoded string"));
+}
+
+#[test]
+fn it_amount_checked_div_rem_int() {
+    let amount = Amount::from_repr(10_000);
+    assert_eq!(amount.checked_div_int(0u32), None);
+    assert_eq!(amount.checked_div_int(0u8), None);
+    assert_eq!(amount.checked_div_int(3u32), Some(amount / 3u32));
+    assert_eq!(amount.checked_div_int(7u8), Some(amount / 7u8));
+    assert_eq!(amount.checked_div_int(7u16), Some(amount / 7u16));
+    assert_eq!(amount.checked_div_int(7u64), Some(amount / 7u64));
+
+    assert_eq!(amount.checked_rem_int(0u32), None);
+    assert_eq!(amount.checked_rem_int(3u32), Some(amount % 3u32));
+    assert_eq!(Amount::from_repr(12_345).checked_rem_int(10u16),
+               Some(Amount::from_repr(12_345) % 10u16));
+    assert_eq!(Amount::max_value().checked_rem_int(u64::MAX), Some(Amount::max_value()));
+
+    let n: usize = 7;
+    assert_eq!(amount.checked_div_int(n), Some(amount / n));
+    assert_eq!(amount.checked_rem_int(n), Some(amount % n));
+    assert_eq!(amount.checked_div_int(0usize), None);
+    assert_eq!(amount.checked_rem_int(0usize), None);
+}
+
+#[test]
+fn it_amount_change() {
+    let due = Amount::from_repr(175_646);
+    assert_eq!(Amount::change(due, due), Ok(Amount::zero()));
+    assert_eq!(Amount::change(due, Amount::from_repr(200_000)), Ok(Amount::from_repr(24_354)));
+    assert_eq!(Amount::change(Amount::zero(), Amount::max_value()), Ok(Amount::max_value()));
+
+    let paid = Amount::from_repr(175_645);
+    let error = Amount::change(due, paid).unwrap_err();
+    assert_eq!(error,
+               InsufficientFunds {
+                   due: due,
+                   paid: paid,
+               });
+    assert_eq!(error.to_string(),
+               "the paid amount (₣ 175.645) is smaller than the due amount (₣ 175.646)");
+    match FractalError::from(error) {
+        FractalError::InsufficientFunds(e) => assert_eq!(e, error),
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn it_amount_rem_semantics() {
+    let amount = Amount::from_repr(12_345);
+    // The divisor of the remainder is in whole credits, while the one of the division is scalar.
+    assert_eq!(amount % 10u32, Amount::from_repr(2_345));
+    assert_eq!(amount / 10u32, Amount::from_repr(1_234));
+    assert_eq!(amount % 1u8, Amount::from_repr(amount.millis() as u64));
+    assert_eq!(amount % 13u32, amount);
+    assert_eq!(amount % 12u64, Amount::from_repr(345));
+
+    let mut rng = thread_rng();
+    for _ in 0..1_000 {
+        let amount = Amount::from_repr(rng.gen());
+        let n = rng.gen_range(1u64, 1_000_000);
+        let rem = amount % n;
+        assert!(rem < Amount::from_units(n).unwrap());
+        let q = amount.units() / n;
+        assert_eq!(Amount::from_units(n).unwrap() * q + rem, amount);
+
+        let mut assigned = amount;
+        assigned %= n;
+        assert_eq!(assigned, rem);
+    }
+}
+
+#[test]
+fn it_amount_break_into() {
+    let denominations: Vec<Amount> = [1, 5, 10, 20, 50, 100, 500]
+        .iter()
+        .map(|&units| Amount::from_units(units).unwrap())
+        .collect();
+    let breakdown = Amount::from_repr(1_234_000).break_into(&denominations);
+    let counts: Vec<u64> = breakdown.iter().map(|&(_, count)| count).collect();
+    assert_eq!(counts, vec![2, 2, 0, 1, 1, 0, 4]);
+    assert_eq!(breakdown[0].0, Amount::from_units(500).unwrap());
+    assert_eq!(breakdown[6].0, Amount::from_units(1).unwrap());
+    let total = breakdown.iter().fold(Amount::zero(), |sum, &(d, count)| sum + d * count);
+    assert_eq!(total, Amount::from_repr(1_234_000));
+
+    // Denominations that don't divide the amount evenly leave a leftover.
+    let breakdown = Amount::from_repr(1_234_567).break_into(&denominations);
+    let total = breakdown.iter().fold(Amount::zero(), |sum, &(d, count)| sum + d * count);
+    assert_eq!(Amount::from_repr(1_234_567) - total, Amount::from_repr(567));
+
+    let breakdown = Amount::from_repr(70_000).break_into(&[Amount::from_repr(30_000),
+                                                            Amount::zero(),
+                                                            Amount::from_repr(40_000)]);
+    assert_eq!(breakdown,
+               vec![(Amount::from_repr(40_000), 1), (Amount::from_repr(30_000), 1),
+                    (Amount::zero(), 0)]);
+    assert!(Amount::from_repr(70_000).break_into(&[]).is_empty());
+}
+
+#[test]
+fn it_amount_mul_reflexive() {
+    let amount = Amount::from_repr(7_000);
+    assert_eq!(10u32 * amount, Amount::from_repr(70_000));
+    assert_eq!(10u8 * amount, amount * 10u8);
+    assert_eq!(10u16 * amount, amount * 10u16);
+    assert_eq!(10u32 * amount, amount * 10u32);
+    assert_eq!(10u64 * amount, amount * 10u64);
+    assert_eq!(10usize * amount, amount * 10usize);
+    assert_eq!(0u32 * amount, Amount::zero());
+}
+
+#[test]
+fn it_amount_parse_thousands_separators() {
+    assert_eq!("1,234.567".parse::<Amount>().unwrap(), Amount::from_repr(1_234_567));
+    assert_eq!("1,000".parse::<Amount>().unwrap(), Amount::from_repr(1_000_000));
+    assert_eq!("+12,345,678.9".parse::<Amount>().unwrap(), Amount::from_repr(12_345_678_900));
+    assert_eq!("999,999".parse::<Amount>().unwrap(), Amount::from_repr(999_999_000));
+    let max = Amount::max_value();
+    assert_eq!(max.display_grouped().to_string().parse::<Amount>().unwrap(), max);
+
+    assert_eq!("1,,234".parse::<Amount>().unwrap_err().position(), Some(2));
+    assert_eq!(",234".parse::<Amount>().unwrap_err().position(), Some(0));
+    assert_eq!("1,234,".parse::<Amount>().unwrap_err().position(), Some(5));
+    assert_eq!("12,34".parse::<Amount>().unwrap_err().position(), Some(2));
+    assert_eq!("1234,567".parse::<Amount>().unwrap_err().position(), Some(4));
+    assert_eq!("1,2345".parse::<Amount>().unwrap_err().position(), Some(1));
+    assert_eq!("+1,23".parse::<Amount>().unwrap_err().position(), Some(2));
+    assert!(",".parse::<Amount>().is_err());
+    assert!(",.5".parse::<Amount>().is_err());
+    assert_eq!("1.234,5".parse::<Amount>().unwrap_err().position(), Some(5));
+    assert_eq!("1,2a4".parse::<Amount>().unwrap_err().position(), Some(3));
+    assert!("18,446,744,073,709,552".parse::<Amount>().is_err());
+}
+
+#[test]
+fn it_amount_list_display() {
+    let amounts = [Amount::from_repr(175_646), Amount::from_repr(5), Amount::from_repr(56_000)];
+    assert_eq!(format!("{:.2}", AmountList(&amounts)), "₣ 175.65, ₣ 0.01, ₣ 56.00");
+    assert_eq!(format!("{}", AmountList(&amounts)), "₣ 175.646, ₣ 0.005, ₣ 56");
+    assert_eq!(format!("{:.0}", AmountList(&amounts[..1])), "₣ 176");
+    assert_eq!(format!("{}", AmountList(&[])), "");
+}
+
+#[test]
+fn it_amount_parse_currency_symbol() {
+    for &repr in &[0, 5, 175_646, 1_234_567, u64::MAX] {
+        let amount = Amount::from_repr(repr);
+        assert_eq!(format!("{} {}", CURRENCY_SYMBOL, amount).parse::<Amount>().unwrap(),
+                   amount);
+        assert_eq!(format!("{}{}", CURRENCY_SYMBOL, amount).parse::<Amount>().unwrap(), amount);
+        assert_eq!(amount.format_currency(CURRENCY_SYMBOL, true, true).parse::<Amount>().unwrap(),
+                   amount);
+        assert_eq!(format!("{} {}", CURRENCY_SYMBOL, amount.display_grouped())
+                       .parse::<Amount>()
+                       .unwrap(),
+                   amount);
+    }
+    assert_eq!(" ₣  175.646 ".parse::<Amount>().unwrap(), Amount::from_repr(175_646));
+    assert_eq!("₣ +175".parse::<Amount>().unwrap(), Amount::from_repr(175_000));
+
+    // Inputs without the symbol are parsed as before.
+    assert!(" 175.646".parse::<Amount>().is_err());
+    assert!("175.646 ".parse::<Amount>().is_err());
+    assert!("175.646₣".parse::<Amount>().is_err());
+    assert!("₣".parse::<Amount>().is_err());
+    assert!("₣ ₣ 175".parse::<Amount>().is_err());
+
+    // Error positions are relative to the whole string.
+    assert_eq!("₣ 175.6a".parse::<Amount>().unwrap_err().position(), Some(9));
+    assert_eq!("₣ 17a".parse::<Amount>().unwrap_err().position(), Some(6));
+    assert_eq!("₣ 1,23".parse::<Amount>().unwrap_err().position(), Some(5));
+}
+
+#[test]
+fn it_walletaddress_from_body_str() {
+    let addr = WalletAddress::from_data([0x00, 0x11, 0x2A, 0x44, 0xCD, 0xFF, 0xE0]);
+    let addr_str = addr.to_string();
+    assert_eq!(WalletAddress::from_body_str(&addr_str[2..]).unwrap(), addr);
+    let fletcher = addr.to_string_with_scheme(ChecksumScheme::Fletcher);
+    assert_eq!(WalletAddress::from_body_str(&fletcher[2..]).unwrap(), addr);
+
+    assert!(WalletAddress::from_body_str(&addr_str).is_err());
+    assert!(WalletAddress::from_body_str("").is_err());
+    assert!(WalletAddress::from_body_str("111111112").is_err());
+    let testnet = WalletAddress::from([0x01, 0x11, 0x2A, 0x44, 0xCD, 0xFF, 0xE0]);
+    assert!(WalletAddress::from_body_str(&testnet.to_string()[2..]).is_err());
+
+    let error = WalletAddress::from_body_str("11111O111").unwrap_err();
+    assert!(error.description().contains("the character 'O' at position 5"));
+}
+
+#[test]
+fn it_amount_saturating_to_scaled() {
+    let amount = Amount::from_repr(175_646);
+    assert_eq!(amount.saturating_to_scaled(0), 176);
+    assert_eq!(amount.saturating_to_scaled(3), 175_646);
+    assert_eq!(amount.saturating_to_scaled(8), 17_564_600_000);
+    assert_eq!(amount.saturating_to_scaled(8),
+               amount.to_iso_minor_units(8, RoundingMode::HalfUp));
+
+    assert_eq!(amount.saturating_to_scaled(18), u64::MAX);
+    assert_eq!(Amount::max_value().saturating_to_scaled(4), u64::MAX);
+    assert_eq!(Amount::max_value().saturating_to_scaled(3), u64::MAX);
+    assert_eq!(Amount::from_repr(1).saturating_to_scaled(22), 10_000_000_000_000_000_000);
+    assert_eq!(Amount::from_repr(1).saturating_to_scaled(23), u64::MAX);
+    assert_eq!(Amount::from_repr(1).saturating_to_scaled(u32::max_value()), u64::MAX);
+    assert_eq!(Amount::zero().saturating_to_scaled(u32::max_value()), 0);
+}